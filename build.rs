@@ -0,0 +1,46 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(cai_cyclic_link_section)");
+    println!("cargo:rustc-check-cfg=cfg(cai_cyclic_simd)");
+    println!("cargo:rerun-if-env-changed=CAI_CYCLIC_LINK_SECTION");
+
+    // The tables are only moved when a section is actually named, so `--all-features`
+    // builds, such as for docs, still work without the variable.
+    if env::var_os("CARGO_FEATURE_LINK_SECTION").is_some() {
+        match env::var("CAI_CYCLIC_LINK_SECTION") {
+            Ok(ref section) if !section.is_empty() => {
+                println!("cargo:rustc-cfg=cai_cyclic_link_section");
+                println!("cargo:rustc-env=CAI_CYCLIC_LINK_SECTION={}", section);
+            },
+            _ => println!("cargo:warning=`link-section` is enabled but \
+                           CAI_CYCLIC_LINK_SECTION isn't set, so the tables stay in \
+                           their default section"),
+        }
+    }
+
+    // `portable_simd` is unstable, so the feature is ignored on other compilers and
+    // `--all-features` builds still work on stable.
+    if env::var_os("CARGO_FEATURE_PORTABLE_SIMD").is_some() {
+        if is_nightly() {
+            println!("cargo:rustc-cfg=cai_cyclic_simd");
+        } else {
+            println!("cargo:warning=`portable_simd` requires a nightly compiler, so the \
+                      `simd` module is left out");
+        }
+    }
+}
+
+/// Check if the compiler building the crate accepts unstable features.
+fn is_nightly() -> bool {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+
+    match Command::new(rustc).arg("--version").output() {
+        Ok(out) => {
+            let version = String::from_utf8_lossy(&out.stdout);
+            version.contains("-nightly") || version.contains("-dev")
+        },
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,150 @@
+//! End-to-end loopback simulation of the (17, 9, 5) code and its DMR variant.
+//!
+//! Random data words are encoded, modulated, passed through an additive white Gaussian
+//! noise channel, demodulated to soft bits, and decoded with each of the hard and soft
+//! decoders, and the word error rate of each is printed for a range of SNRs.
+//!
+//! Two links are simulated:
+//!
+//! - BPSK carrying the extended (18, 9, 6) word, decoded with the hard decoder, the
+//!   17-bit soft decoder, and the 18-bit soft decoder that also uses the parity bit.
+//! - 4-level symbols carrying the DMR (16, 7, 6) word as 8 dibits, decoded with the
+//!   hard decoder and the DMR soft decoder.
+//!
+//! Usage: `cargo run --release --features sim --example loopback [words] [min_snr]
+//! [max_snr] [step]`
+
+extern crate cai_cyclic;
+
+use std::env;
+
+use cai_cyclic::sim::{AwgnChannel, RandomSource, SplitMix64};
+use cai_cyclic::soft::{self, LlrConverter};
+
+/// Word error counts for each decoder on one link.
+struct Errors {
+    names: &'static [&'static str],
+    counts: Vec<usize>,
+}
+
+impl Errors {
+    fn new(names: &'static [&'static str]) -> Self {
+        Errors { names, counts: vec![0; names.len()] }
+    }
+
+    /// Record whether each decoder recovered the expected data.
+    fn record<T: PartialEq>(&mut self, expected: T, results: &[Option<T>]) {
+        assert_eq!(results.len(), self.counts.len());
+
+        for (count, res) in self.counts.iter_mut().zip(results) {
+            if res.as_ref() != Some(&expected) {
+                *count += 1;
+            }
+        }
+    }
+}
+
+/// Simulate the BPSK link carrying extended codewords.
+fn run_bpsk<R: RandomSource>(rng: &mut R, words: usize, snr_db: f64) -> Errors {
+    let mut chan = AwgnChannel::new(snr_db, SplitMix64::new(rng.next_u64()));
+    let conv = LlrConverter::new(chan.noise_var() as f32);
+    let mut errs = Errors::new(&["hard", "soft17", "soft18"]);
+
+    for _ in 0..words {
+        let data = rng.next_below(1 << 9) as u16;
+        let word = cai_cyclic::encode_extended(data);
+
+        let llrs = (0..18)
+            .map(|i| conv.quantize(conv.binary(chan.sample(word >> (17 - i) & 1 == 1) as f32)))
+            .collect::<Vec<i8>>();
+
+        let hard = llrs[..17].iter().fold(0, |w, &llr| w << 1 | (llr < 0) as u32);
+
+        errs.record(data, &[
+            cai_cyclic::decode(hard).map(|(d, _)| d),
+            soft::decode(&llrs[..17]).map(|(d, _)| d),
+            soft::decode_extended(&llrs[..]).map(|(d, _)| d),
+        ]);
+    }
+
+    errs
+}
+
+/// Simulate the 4-level link carrying DMR codewords.
+fn run_dibit<R: RandomSource>(rng: &mut R, words: usize, snr_db: f64) -> Errors {
+    let mut chan = AwgnChannel::with_signal_power(snr_db, 5.0, SplitMix64::new(rng.next_u64()));
+    let conv = LlrConverter::new(chan.noise_var() as f32);
+    let mut errs = Errors::new(&["hard", "soft16"]);
+
+    for _ in 0..words {
+        let data = rng.next_below(1 << 7) as u8;
+        let word = cai_cyclic::encode_dmr(data);
+
+        let mut llrs = [0i8; 16];
+
+        for (s, pair) in llrs.chunks_mut(2).enumerate() {
+            let sample = match word >> (14 - 2 * s) & 0b11 {
+                0b01 => 3.0,
+                0b00 => 1.0,
+                0b10 => -1.0,
+                _ => -3.0,
+            };
+
+            let (msb, lsb) = conv.dibit(chan.pass(sample) as f32);
+            pair[0] = conv.quantize(msb);
+            pair[1] = conv.quantize(lsb);
+        }
+
+        let hard = llrs.iter().fold(0, |w, &llr| w << 1 | (llr < 0) as u16);
+
+        errs.record(data, &[
+            cai_cyclic::decode_dmr(hard).map(|(d, _)| d),
+            soft::decode_dmr(&llrs[..]).map(|(d, _)| d),
+        ]);
+    }
+
+    errs
+}
+
+/// Print one row of word error rates.
+fn print_row(link: &str, snr_db: f64, words: usize, errs: &Errors) {
+    print!("{:>6} {:>6.1}", link, snr_db);
+
+    for (name, &count) in errs.names.iter().zip(&errs.counts) {
+        print!("  {}={:.2e}", name, count as f64 / words as f64);
+    }
+
+    println!();
+}
+
+fn main() {
+    let args = env::args().skip(1)
+        .map(|a| a.parse::<f64>().expect("invalid numeric argument"))
+        .collect::<Vec<_>>();
+
+    let words = args.first().map_or(5000, |&w| w as usize);
+    let min_snr = args.get(1).cloned().unwrap_or(0.0);
+    let max_snr = args.get(2).cloned().unwrap_or(8.0);
+    let step = args.get(3).cloned().unwrap_or(1.0);
+
+    assert!(words > 0 && step > 0.0 && min_snr <= max_snr);
+
+    let mut rng = SplitMix64::new(0x5eed);
+    let mut snr_db = min_snr;
+
+    while snr_db <= max_snr + 1e-9 {
+        let bpsk = run_bpsk(&mut rng, words, snr_db);
+        print_row("bpsk", snr_db, words, &bpsk);
+
+        // Soft decoding never loses to hard decoding by more than sampling noise.
+        assert!(bpsk.counts[1] <= bpsk.counts[0] + words / 100 + 10);
+        assert!(bpsk.counts[2] <= bpsk.counts[1] + words / 100 + 10);
+
+        let dibit = run_dibit(&mut rng, words, snr_db);
+        print_row("dibit", snr_db, words, &dibit);
+
+        assert!(dibit.counts[1] <= dibit.counts[0] + words / 100 + 10);
+
+        snr_db += step;
+    }
+}
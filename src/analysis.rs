@@ -0,0 +1,494 @@
+//! Structural analysis of the base code and of codes given by other generators.
+//!
+//! ## Equivalence
+//!
+//! Vendor documentation describes the same code in many ways: as a generator
+//! polynomial, as a systematic or non-systematic generator matrix, and with the bits in
+//! different orders. These routines check whether such a description generates the
+//! same code as the one implemented here, or one that is equivalent up to a
+//! permutation of the bit positions.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::analysis;
+//!
+//! // The other degree-8 factor of x^17 + 1 generates an equivalent code.
+//! let rows = analysis::poly_rows(0b111010111);
+//!
+//! assert!(!analysis::same_code(&rows));
+//! assert_eq!(analysis::equivalence(&rows).map(|p| p.multiplier), Some(3));
+//! ```
+//!
+//! ## Bounds
+//!
+//! For arbitrary codes built at runtime, [`Bounds`](struct.Bounds.html) reports the
+//! actual minimum distance alongside the Singleton and Hamming bounds, to show how
+//! sensible the code is.
+//!
+//! ```rust
+//! use cai_cyclic::analysis::{self, Bounds};
+//!
+//! // The (23, 12, 7) Golay code is perfect.
+//! let b = Bounds::of(23, &analysis::cyclic_rows(23, 0b110001110101));
+//!
+//! assert_eq!((b.n, b.k, b.d_min), (23, 12, 7));
+//! assert!(b.is_perfect());
+//! ```
+//!
+//! ## Weight distribution
+//!
+//! [`weights`](fn.weights.html) counts the codewords of each weight, which determines
+//! the probability of an undetected error on a binary symmetric channel.
+//!
+//! ```rust
+//! use cai_cyclic::analysis;
+//!
+//! let dist = analysis::weights();
+//!
+//! assert_eq!(dist[..5], [1, 0, 0, 0, 0]);
+//! assert_eq!(dist[5], 34);
+//! assert!(analysis::p_undetected(&dist, 1e-3) < 1e-13);
+//! ```
+//!
+//! ## Performance
+//!
+//! [`characterize`](fn.characterize.html) runs a decoder over every possible error
+//! pattern and tabulates the exact outcome probabilities for each error weight.
+//!
+//! ```rust
+//! use cai_cyclic::analysis;
+//!
+//! let table = analysis::characterize(cai_cyclic::decode, 0);
+//!
+//! assert_eq!(table[2].p_success(), 1.0);
+//! assert_eq!(table[3].p_success(), 0.0);
+//! assert!(table[3].p_miscorrected() > 0.0);
+//! ```
+
+/// Compute the 9 generator matrix rows x<sup>i</sup>g(x) of the length-17 cyclic code
+/// with the given degree-8 generator polynomial, where bit `i` of the polynomial is the
+/// coefficient of x<sup>i</sup>.
+pub fn poly_rows(poly: u32) -> Vec<u32> {
+    assert_eq!(poly >> 8, 1);
+    (0..9).map(|i| poly << i).collect()
+}
+
+/// Compute the generator matrix rows x<sup>i</sup>g(x) of the length-`n` cyclic code
+/// with the given generator polynomial, in the same form as `poly_rows()`.
+///
+/// The result only forms a cyclic code if the polynomial divides x<sup>n</sup> + 1.
+pub fn cyclic_rows(n: usize, poly: u32) -> Vec<u32> {
+    assert!(poly != 0 && n <= 32);
+
+    let degree = 31 - poly.leading_zeros() as usize;
+    assert!(degree < n);
+
+    (0..n - degree).map(|i| poly << i).collect()
+}
+
+/// Check if the given 17-bit generator matrix rows generate exactly the base (17, 9, 5)
+/// code.
+///
+/// The rows can be in any form, systematic or not, as long as they span the code.
+pub fn same_code(rows: &[u32]) -> bool {
+    rows.iter().all(|&w| w >> 17 == 0 && ::encode((w >> 8) as u16) == w) &&
+        rank(rows) == 9
+}
+
+/// An affine permutation of bit positions, mapping position `i` (counting from the LSB)
+/// to position `(multiplier * i + shift) mod 17`.
+///
+/// These include all cyclic rotations (multiplier 1), the bit reversal (multiplier 16,
+/// shift 16), and the multiplier permutations relating different cyclic codes of the
+/// same length.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    /// Multiplier applied to each position, from 1 to 16.
+    pub multiplier: u8,
+    /// Shift applied after multiplying, from 0 to 16.
+    pub shift: u8,
+}
+
+impl Permutation {
+    /// Create a new permutation with the given multiplier and shift.
+    pub fn new(multiplier: u8, shift: u8) -> Self {
+        assert!((1..17).contains(&multiplier) && shift < 17);
+        Permutation { multiplier, shift }
+    }
+
+    /// Move each bit of the given 17-bit word to its permuted position.
+    pub fn apply(&self, word: u32) -> u32 {
+        (0..17).filter(|&i| word >> i & 1 == 1).fold(0, |out, i| {
+            out | 1 << ((self.multiplier as u32 * i + self.shift as u32) % 17)
+        })
+    }
+}
+
+/// Search for a permutation that maps the code generated by the given rows onto the base
+/// code.
+///
+/// If the code is the same, the identity permutation is returned. Otherwise the first
+/// matching affine permutation is returned, or `None` if the code isn't equivalent under
+/// any affine permutation. Equivalence under an arbitrary permutation is much more
+/// expensive to decide and isn't checked.
+pub fn equivalence(rows: &[u32]) -> Option<Permutation> {
+    if rank(rows) != 9 || rows.iter().any(|&w| w >> 17 != 0) {
+        return None;
+    }
+
+    (1..17).flat_map(|m| (0..17).map(move |s| Permutation::new(m, s)))
+        .find(|p| same_code(&rows.iter().map(|&w| p.apply(w)).collect::<Vec<_>>()))
+}
+
+/// Parameters of a binary linear code along with bounds on them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    /// Codeword length.
+    pub n: usize,
+    /// Number of data bits.
+    pub k: usize,
+    /// Actual minimum distance, or 0 if the code has no nonzero codewords.
+    pub d_min: usize,
+    /// Singleton bound on the minimum distance, n - k + 1.
+    pub singleton: usize,
+    /// Hamming bound on the number of correctable errors.
+    pub hamming: usize,
+}
+
+impl Bounds {
+    /// Compute the parameters of the length-`n` code spanned by the given generator
+    /// matrix rows.
+    ///
+    /// The minimum distance is found by enumerating every codeword, so the code
+    /// dimension is limited to 24.
+    pub fn of(n: usize, rows: &[u32]) -> Self {
+        assert!(n <= 32);
+        assert!(rows.iter().all(|&w| n == 32 || w >> n == 0));
+
+        let basis = reduce(rows);
+        let k = basis.len();
+        assert!(k <= 24);
+
+        // Walk the codewords in Gray code order, so each differs from the last by a
+        // single basis row.
+        let d_min = (1..1u32 << k).scan(0, |word, i| {
+            *word ^= basis[i.trailing_zeros() as usize];
+            Some(word.count_ones() as usize)
+        }).min().unwrap_or(0);
+
+        let hamming = (0..n + 1).take_while(|&t| {
+            (0..t + 1).map(|i| binomial(n, i)).sum::<u64>() <= 1 << (n - k)
+        }).last().unwrap_or(0);
+
+        Bounds {
+            n,
+            k,
+            d_min,
+            singleton: n - k + 1,
+            hamming,
+        }
+    }
+
+    /// Number of errors the code can correct, from its minimum distance.
+    pub fn correctable(&self) -> usize { self.d_min.saturating_sub(1) / 2 }
+
+    /// Check if the code meets the Singleton bound, making it maximum distance
+    /// separable.
+    pub fn is_mds(&self) -> bool { self.d_min == self.singleton }
+
+    /// Check if the code meets the Hamming bound with equality, making it perfect.
+    pub fn is_perfect(&self) -> bool {
+        let t = self.correctable();
+        (0..t + 1).map(|i| binomial(self.n, i)).sum::<u64>() == 1 << (self.n - self.k)
+    }
+}
+
+/// Count the codewords of the base code with each weight from 0 to 17.
+pub fn weights() -> [u64; 18] {
+    ::codewords().fold([0; 18], |mut dist, w| {
+        dist[w.count_ones() as usize] += 1;
+        dist
+    })
+}
+
+/// Count the codewords with each weight from 0 to `n` in the length-`n` code spanned by
+/// the given generator matrix rows.
+///
+/// Every codeword is enumerated, so the code dimension is limited to 24.
+pub fn weight_distribution(n: usize, rows: &[u32]) -> Vec<u64> {
+    assert!(n <= 32);
+    assert!(rows.iter().all(|&w| n == 32 || w >> n == 0));
+
+    let basis = reduce(rows);
+    assert!(basis.len() <= 24);
+
+    let mut dist = vec![0; n + 1];
+    dist[0] = 1;
+
+    // Walk the codewords in Gray code order, as in `Bounds::of()`.
+    (1..1u32 << basis.len()).fold(0, |word, i| {
+        let word = word ^ basis[i.trailing_zeros() as usize];
+        dist[word.count_ones() as usize] += 1;
+        word
+    });
+
+    dist
+}
+
+/// Compute the probability that errors on a binary symmetric channel with the given
+/// crossover probability turn a codeword into a different codeword, going undetected
+/// by any decoder, from the code's weight distribution.
+pub fn p_undetected(dist: &[u64], p: f64) -> f64 {
+    let n = dist.len() - 1;
+
+    dist.iter().enumerate().skip(1)
+        .map(|(w, &count)| count as f64 * p.powi(w as i32) * (1.0 - p).powi((n - w) as i32))
+        .sum()
+}
+
+/// Counts of decoder outcomes over all error patterns of one weight.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outcomes {
+    /// Patterns where the original data was recovered.
+    pub success: u32,
+    /// Patterns where the decoder reported an unrecoverable error.
+    pub detected: u32,
+    /// Patterns where the decoder returned the wrong data.
+    pub miscorrected: u32,
+}
+
+impl Outcomes {
+    /// Total number of patterns.
+    pub fn total(&self) -> u32 { self.success + self.detected + self.miscorrected }
+
+    /// Probability of recovering the original data.
+    pub fn p_success(&self) -> f64 { self.success as f64 / self.total() as f64 }
+
+    /// Probability of detecting an unrecoverable error.
+    pub fn p_detected(&self) -> f64 { self.detected as f64 / self.total() as f64 }
+
+    /// Probability of silently returning the wrong data.
+    pub fn p_miscorrected(&self) -> f64 { self.miscorrected as f64 / self.total() as f64 }
+}
+
+/// Run the given decoder over every 17-bit error pattern applied to the codeword for
+/// the given data, and tabulate the outcomes indexed by error weight from 0 to 17.
+///
+/// Since every error pattern of a given weight is equally likely on a binary symmetric
+/// channel, the resulting probabilities are exact conditioned on the weight.
+pub fn characterize<F>(mut decoder: F, data: u16) -> [Outcomes; 18] where
+    F: FnMut(u32) -> Option<(u16, usize)>
+{
+    let word = ::encode(data);
+    let mut table = [Outcomes::default(); 18];
+
+    for err in 0..1u32 << 17 {
+        let out = &mut table[err.count_ones() as usize];
+
+        match decoder(word ^ err) {
+            Some((d, _)) if d == data => out.success += 1,
+            Some(_) => out.miscorrected += 1,
+            None => out.detected += 1,
+        }
+    }
+
+    table
+}
+
+/// Compute the binomial coefficient C(n, k).
+fn binomial(n: usize, k: usize) -> u64 {
+    (0..k as u64).fold(1, |c, i| c * (n as u64 - i) / (i + 1))
+}
+
+/// Reduce the given rows to a linearly independent basis of their span.
+fn reduce(rows: &[u32]) -> Vec<u32> {
+    let mut basis: Vec<u32> = Vec::new();
+
+    for &row in rows {
+        // Reduce against the basis, which is kept sorted with distinct leading bits.
+        let r = basis.iter().fold(row, |r, &b| r.min(r ^ b));
+
+        if r != 0 {
+            basis.push(r);
+            basis.sort_by(|a, b| b.cmp(a));
+        }
+    }
+
+    basis
+}
+
+/// Compute the rank over GF(2) of the given rows.
+fn rank(rows: &[u32]) -> usize {
+    let mut rows = rows.to_vec();
+    let mut rank = 0;
+
+    for bit in (0..32).rev() {
+        let pivot = match (rank..rows.len()).find(|&r| rows[r] >> bit & 1 == 1) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        rows.swap(rank, pivot);
+
+        for r in 0..rows.len() {
+            if r != rank && rows[r] >> bit & 1 == 1 {
+                rows[r] ^= rows[rank];
+            }
+        }
+
+        rank += 1;
+    }
+
+    rank
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use encode;
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(rank(&[]), 0);
+        assert_eq!(rank(&[0, 0]), 0);
+        assert_eq!(rank(&[0b110, 0b011, 0b101]), 2);
+        assert_eq!(rank(&[0b100, 0b011, 0b101]), 3);
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(reduce(&[0b110, 0b011, 0b101]).len(), 2);
+        assert_eq!(reduce(&[0, 0b1]), vec![0b1]);
+
+        for rows in &[vec![0b110, 0b011, 0b101], poly_rows(0b100111001)] {
+            assert_eq!(reduce(rows).len(), rank(rows));
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let b = Bounds::of(17, &poly_rows(0b100111001));
+        assert_eq!(b, Bounds { n: 17, k: 9, d_min: 5, singleton: 9, hamming: 2 });
+        assert_eq!(b.correctable(), 2);
+        assert!(!b.is_perfect());
+        assert!(!b.is_mds());
+
+        // (7, 4, 3) Hamming code.
+        let b = Bounds::of(7, &cyclic_rows(7, 0b1011));
+        assert_eq!((b.k, b.d_min, b.hamming), (4, 3, 1));
+        assert!(b.is_perfect());
+
+        // Repetition code.
+        let b = Bounds::of(5, &[0b11111]);
+        assert_eq!((b.k, b.d_min), (1, 5));
+        assert!(b.is_mds());
+        assert!(b.is_perfect());
+
+        // Dependent rows don't add to the dimension.
+        let b = Bounds::of(5, &[0b11000, 0b00011, 0b11011]);
+        assert_eq!((b.k, b.d_min), (2, 2));
+
+        assert_eq!(Bounds::of(4, &[]).d_min, 0);
+        assert_eq!(binomial(17, 2), 136);
+        assert_eq!(binomial(23, 3), 1771);
+    }
+
+    #[test]
+    fn test_weights() {
+        let dist = weights();
+        assert_eq!(dist, [1, 0, 0, 0, 0, 34, 68, 68, 85, 85, 68, 68, 34, 0, 0, 0, 0, 1]);
+        assert_eq!(dist.iter().sum::<u64>(), 512);
+        assert_eq!(weight_distribution(17, &poly_rows(0b100111001)), dist.to_vec());
+
+        // (7, 4, 3) Hamming code.
+        assert_eq!(weight_distribution(7, &cyclic_rows(7, 0b1011)), [1, 0, 0, 7, 7, 0, 0, 1]);
+
+        // Dependent rows don't add codewords.
+        assert_eq!(weight_distribution(5, &[0b11000, 0b00011, 0b11011]), [1, 0, 2, 0, 1, 0]);
+        assert_eq!(weight_distribution(3, &[]), [1, 0, 0, 0]);
+
+        assert_eq!(p_undetected(&dist, 0.0), 0.0);
+        assert!((p_undetected(&dist, 0.5) - 511.0 / (1 << 17) as f64).abs() < 1e-15);
+        assert!((p_undetected(&[1, 0, 1], 0.1) - 0.01).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_characterize() {
+        let table = characterize(::decode, 0b110010011);
+
+        for (t, out) in table.iter().enumerate() {
+            assert_eq!(out.total() as u64, binomial(17, t));
+        }
+
+        for out in &table[..3] {
+            assert_eq!(out.success, out.total());
+        }
+
+        for out in &table[3..] {
+            assert_eq!(out.success, 0);
+        }
+
+        // A weight-3 pattern is miscorrected exactly when it lies within a weight-5
+        // codeword relative to the transmitted one.
+        let a5 = ::codewords().filter(|w| w.count_ones() == 5).count() as u32;
+        assert_eq!(table[3].miscorrected, a5 * 10);
+
+        // The all-ones pattern maps to the complement codeword.
+        assert_eq!(table[17], Outcomes { success: 0, detected: 0, miscorrected: 1 });
+
+        assert_eq!(table[1].p_success(), 1.0);
+        assert_eq!(table[17].p_miscorrected(), 1.0);
+        assert_eq!(table[17].p_detected(), 0.0);
+    }
+
+    #[test]
+    fn test_same_code() {
+        let sys = (0..9).map(|i| encode(1 << i)).collect::<Vec<_>>();
+        assert!(same_code(&sys));
+
+        assert!(same_code(&poly_rows(0b100111001)));
+
+        // Sums of rows still span the code.
+        let mixed = (0..9).map(|i| sys[i] ^ sys[(i + 1) % 9] ^ sys[(i + 3) % 9])
+            .collect::<Vec<_>>();
+        assert_eq!(rank(&mixed), 9);
+        assert!(same_code(&mixed));
+
+        // Too few rows, or rows outside the code.
+        assert!(!same_code(&sys[..8]));
+        assert!(!same_code(&poly_rows(0b100000001)));
+    }
+
+    #[test]
+    fn test_permutation() {
+        let p = Permutation::new(1, 1);
+        assert_eq!(p.apply(0b10000000000000001), 0b00000000000000011);
+
+        let p = Permutation::new(16, 16);
+        assert_eq!(p.apply(0b10000000000000110), 0b01100000000000001);
+
+        let p = Permutation::new(3, 0);
+        assert_eq!(p.apply(0b111), 0b1001001);
+    }
+
+    #[test]
+    fn test_equivalence() {
+        let sys = (0..9).map(|i| encode(1 << i)).collect::<Vec<_>>();
+        assert_eq!(equivalence(&sys), Some(Permutation::new(1, 0)));
+
+        // Rotated rows, such as with the parity bits first.
+        let rot = sys.iter().map(|&w| (w >> 8 | w << 9) & 0x1ffff).collect::<Vec<_>>();
+        assert!(same_code(&rot));
+
+        // A permutation that isn't an automorphism of the code.
+        let p = Permutation::new(3, 5);
+        let perm = sys.iter().map(|&w| p.apply(w)).collect::<Vec<_>>();
+        assert!(!same_code(&perm));
+        let q = equivalence(&perm).unwrap();
+        assert!(same_code(&perm.iter().map(|&w| q.apply(w)).collect::<Vec<_>>()));
+
+        assert_eq!(equivalence(&poly_rows(0b100000001)), None);
+        assert_eq!(equivalence(&sys[..8]), None);
+    }
+}
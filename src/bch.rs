@@ -0,0 +1,280 @@
+//! Encoding and decoding of the (63, 16, 23) BCH code protecting the P25 network
+//! identifier.
+//!
+//! The generator polynomial for the code, in octal with the x<sup>47</sup> term first,
+//! is given by
+//!
+//! > g(x) = 6331 1413 6723 5453
+//!
+//! and its roots include α<sup>1</sup> through α<sup>22</sup> in GF(2<sup>6</sup>),
+//! generated by x<sup>6</sup> + x + 1. Each 63-bit codeword holds the 16 data bits in
+//! the MSBs, followed by the 47 parity bits, and decoding corrects up to 11 errors.
+//!
+//! ## Network identifier
+//!
+//! The 64-bit NID starting every P25 frame carries the 12-bit network access code (NAC)
+//! and the 4-bit data unit ID (DUID) as the data bits, with the NAC first. A final bit,
+//! in the LSB, is a parity bit fixed by the DUID: 1 for the LDU1 and LDU2 voice frames
+//! and 0 otherwise. This is handled by [`encode_nid`](fn.encode_nid.html) and
+//! [`decode_nid`](fn.decode_nid.html).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::bch;
+//!
+//! let nid = bch::encode_nid(0x293, 0b0101);
+//! assert_eq!(nid & 1, 1);
+//!
+//! // Eleven errors, the most the code can correct.
+//! let rx = nid ^ 0b10110111011 << 40 ^ 0b10010001 << 3;
+//! assert_eq!(bch::decode_nid(rx), Some(((0x293, 0b0101), 11)));
+//! ```
+
+use code::{BlockCode, Encoder, Decoder};
+use gf::GF64;
+
+/// Encode the given 16 data bits into a 63-bit codeword.
+pub fn encode(data: u16) -> u64 {
+    let word = (data as u64) << 47;
+    word | remainder(word)
+}
+
+/// Try to decode the given 63-bit word, correcting up to 11 errors.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 16 data
+/// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
+/// an unrecoverable error.
+pub fn decode(word: u64) -> Option<(u16, usize)> {
+    assert_eq!(word >> 63, 0);
+
+    let mut syn = [0; 2 * T];
+
+    if !syndromes(word, &mut syn) {
+        return Some(((word >> 47) as u16, 0));
+    }
+
+    let (lambda, deg) = locator(&syn)?;
+    let mut fixed = word;
+    let mut found = 0;
+
+    // Chien search for the roots α^-i, which mark an error at x^i.
+    for i in 0..63 {
+        if GF64.eval(&lambda[..deg + 1], GF64.alpha(63 - i)) == 0 {
+            fixed ^= 1 << i;
+            found += 1;
+        }
+    }
+
+    // A locator that doesn't split means there were too many errors.
+    if found != deg || remainder(fixed) != 0 {
+        return None;
+    }
+
+    Some(((fixed >> 47) as u16, found))
+}
+
+/// Encode the given 12-bit NAC and 4-bit DUID into a 64-bit NID, with the DUID's parity
+/// bit in the LSB.
+pub fn encode_nid(nac: u16, duid: u8) -> u64 {
+    assert_eq!(nac >> 12, 0);
+    assert_eq!(duid >> 4, 0);
+
+    encode(nac << 4 | duid as u16) << 1 | nid_parity(duid)
+}
+
+/// Try to decode the given 64-bit NID, correcting up to 11 errors in the codeword.
+///
+/// If decoding was successful, return `Some(((nac, duid), err))`, where `err` is the
+/// number of corrected bits, including the parity bit if it doesn't match the decoded
+/// DUID. Otherwise, return `None` to indicate an unrecoverable error.
+pub fn decode_nid(word: u64) -> Option<((u16, u8), usize)> {
+    let (data, err) = decode(word >> 1)?;
+    let duid = data as u8 & 0b1111;
+
+    Some(((data >> 4, duid), err + (word & 1 != nid_parity(duid)) as usize))
+}
+
+/// The (63, 16, 23) BCH code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bch;
+
+impl Encoder for Bch {
+    type Data = u16;
+    type Codeword = u64;
+
+    fn encode(&self, data: u16) -> u64 { encode(data) }
+}
+
+impl Decoder for Bch {
+    fn decode(&self, word: u64) -> Option<(u16, usize)> { decode(word) }
+}
+
+impl BlockCode for Bch {
+    const N: usize = 63;
+    const K: usize = 16;
+    const D: usize = 23;
+}
+
+impl_dyn_block_code!(Bch, "bch63", u16, 16, u64, 63);
+
+/// Number of correctable errors.
+const T: usize = 11;
+
+/// Generator polynomial, with the x<sup>47</sup> term in the MSB.
+const GEN_POLY: u64 = 0o6331141367235453;
+
+/// Get the NID parity bit for the given DUID.
+fn nid_parity(duid: u8) -> u64 {
+    match duid {
+        0b0101 | 0b1010 => 1,
+        _ => 0,
+    }
+}
+
+/// Compute the remainder of the given 63-bit word divided by the generator polynomial.
+fn remainder(word: u64) -> u64 {
+    (47..63).rev().fold(word, |rem, bit| {
+        if rem >> bit & 1 == 1 { rem ^ GEN_POLY << (bit - 47) } else { rem }
+    })
+}
+
+/// Compute the syndromes S<sub>1</sub> through S<sub>22</sub> of the given word,
+/// returning whether any are nonzero.
+fn syndromes(word: u64, syn: &mut [u8; 2 * T]) -> bool {
+    for (j, s) in syn.iter_mut().enumerate() {
+        *s = (0..63).filter(|&i| word >> i & 1 == 1)
+            .fold(0, |acc, i| acc ^ GF64.alpha(i * (j + 1)));
+    }
+
+    syn.iter().any(|&s| s != 0)
+}
+
+/// Find the error locator polynomial for the given syndromes with the Berlekamp-Massey
+/// algorithm, returning its coefficients from the lowest degree and its degree, or
+/// `None` if it implies more than 11 errors.
+fn locator(syn: &[u8; 2 * T]) -> Option<([u8; 2 * T + 1], usize)> {
+    let mut lambda = [0; 2 * T + 1];
+    let mut prev = [0; 2 * T + 1];
+    let mut len = 0;
+    let mut gap = 1;
+    let mut prev_discr = 1;
+
+    lambda[0] = 1;
+    prev[0] = 1;
+
+    for r in 0..2 * T {
+        let discr = (0..len + 1).fold(0, |acc, i| acc ^ GF64.mul(lambda[i], syn[r - i]));
+
+        if discr == 0 {
+            gap += 1;
+            continue;
+        }
+
+        let scale = GF64.div(discr, prev_discr);
+        let last = lambda;
+
+        for i in gap..2 * T + 1 {
+            lambda[i] ^= GF64.mul(scale, prev[i - gap]);
+        }
+
+        if 2 * len <= r {
+            len = r + 1 - len;
+            prev = last;
+            prev_discr = discr;
+            gap = 1;
+        } else {
+            gap += 1;
+        }
+    }
+
+    let deg = lambda.iter().rposition(|&c| c != 0).unwrap();
+
+    if len > T || deg != len {
+        return None;
+    }
+
+    Some((lambda, deg))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sim::{RandomSource, SplitMix64};
+
+    /// Pick a random error pattern of the given weight.
+    fn random_errors(rng: &mut SplitMix64, weight: u32) -> u64 {
+        let mut pat = 0u64;
+
+        while pat.count_ones() < weight {
+            pat |= 1 << (rng.next_u64() % 63);
+        }
+
+        pat
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(0), 0);
+        assert_eq!(encode(1), GEN_POLY);
+
+        // The generator has the first 22 powers of α as roots.
+        for j in 1..2 * T + 1 {
+            let v = (0..48).filter(|&i| GEN_POLY >> i & 1 == 1)
+                .fold(0, |acc, i| acc ^ GF64.alpha(i * j));
+
+            assert_eq!(v, 0);
+        }
+
+        for d in 1..=u16::MAX {
+            let w = encode(d);
+            assert_eq!(remainder(w), 0);
+            assert!(w.count_ones() >= 23);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut rng = SplitMix64::new(41);
+
+        for _ in 0..500 {
+            let d = rng.next_u64() as u16;
+            let w = encode(d);
+            assert_eq!(decode(w), Some((d, 0)));
+
+            for weight in 1..T as u32 + 1 {
+                let pat = random_errors(&mut rng, weight);
+                assert_eq!(decode(w ^ pat), Some((d, weight as usize)));
+            }
+        }
+
+        // Patterns beyond the correction capability are mostly detected, since the
+        // decoding spheres cover only a small part of the space.
+        let mut detected = 0;
+
+        for _ in 0..500 {
+            let w = encode(rng.next_u64() as u16);
+
+            match decode(w ^ random_errors(&mut rng, 12)) {
+                None => detected += 1,
+                Some((_, err)) => assert!(err <= T),
+            }
+        }
+
+        assert!(detected > 450);
+    }
+
+    #[test]
+    fn test_nid() {
+        assert_eq!(encode_nid(0x293, 0b0101) & 1, 1);
+        assert_eq!(encode_nid(0x293, 0b1010) & 1, 1);
+        assert_eq!(encode_nid(0x293, 0b0000) & 1, 0);
+        assert_eq!(encode_nid(0x293, 0b0111) >> 1, encode(0x2937));
+
+        let nid = encode_nid(0xf7e, 0b0011);
+        assert_eq!(decode_nid(nid), Some(((0xf7e, 0b0011), 0)));
+        assert_eq!(decode_nid(nid ^ 1), Some(((0xf7e, 0b0011), 1)));
+        assert_eq!(decode_nid(nid ^ 0b110 << 60), Some(((0xf7e, 0b0011), 2)));
+    }
+}
@@ -0,0 +1,395 @@
+//! Encoding and decoding of DMR's BPTC (196, 96) block product turbo code.
+//!
+//! The BPTC carries the 96-bit payloads of DMR data and link control bursts. Before
+//! interleaving, the 196 transmitted bits start with a reserved bit, followed by a 13 ×
+//! 15 matrix in row-major order:
+//!
+//! - Rows 0 to 8 are (15, 11, 3) Hamming row codewords. Their 99 data bits are 3
+//!   reserved bits followed by the 96 payload bits.
+//! - Rows 9 to 12 hold the parity of the (13, 9, 3) Hamming column code over each of the
+//!   15 columns.
+//!
+//! The matrix bit at index `i`, counting the leading reserved bit as index 0, is sent at
+//! position `181 i mod 196`. The reserved bits are sent as zero and ignored on receive.
+//! The same permutation is used by other DMR payloads, so it's available separately as
+//! [`interleave`](fn.interleave.html) and [`deinterleave`](fn.deinterleave.html), with
+//! in-place variants that need no scratch buffer.
+//!
+//! The code is a [`ProductCode`](../product/struct.ProductCode.html) of the
+//! [`hamming`](../hamming/index.html) codes, with the reserved bit as padding and the
+//! permutation as its interleaver. Decoding alternates row and column passes, so a burst
+//! that overwhelms one row is repaired by the columns crossing it, and the other way
+//! around. [`Bptc`](struct.Bptc.html) provides the generic [`code`](../code/index.html)
+//! traits.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::bptc;
+//!
+//! let payload = [0x00, 0x10, 0x20, 0x00, 0x0c, 0x30, 0x2f, 0x9b, 0xe5, 0xda, 0xd4, 0x5a];
+//! let mut bits = bptc::encode(&payload);
+//!
+//! // Bursts of 5 errors on the air, spread across columns by the interleaver.
+//! for bit in &mut bits[20..25] {
+//!     *bit = !*bit;
+//! }
+//!
+//! for bit in &mut bits[150..155] {
+//!     *bit = !*bit;
+//! }
+//!
+//! assert_eq!(bptc::decode(&bits), Some((payload, 10)));
+//! ```
+
+use std::sync::OnceLock;
+
+use code::{BlockCode, Decoder, DynCode, Encoder, from_symbols, to_symbols};
+use hamming::{Hamming13, Hamming15};
+use product::ProductCode;
+
+/// Number of reserved bits at the start of the first row.
+const RESERVED: usize = 3;
+
+/// Maximum number of row and column pass pairs when decoding.
+const ITERATIONS: usize = 5;
+
+/// Get the shared product code, built on first use.
+fn code() -> &'static ProductCode<Hamming15, Hamming13> {
+    static CODE: OnceLock<ProductCode<Hamming15, Hamming13>> = OnceLock::new();
+
+    CODE.get_or_init(|| {
+        ProductCode::new(Hamming15, Hamming13)
+            .with_padding(1)
+            .with_interleaver((0..196).map(position).collect())
+            .with_iterations(ITERATIONS)
+    })
+}
+
+/// Encode the given 96-bit payload, with the first transmitted bit in the MSB of the
+/// first byte, into 196 interleaved bits in transmission order.
+pub fn encode(data: &[u8; 12]) -> [bool; 196] {
+    let bits = [false; RESERVED].iter().cloned()
+        .chain((0..96).map(|i| data[i / 8] >> (7 - i % 8) & 1 == 1))
+        .collect::<Vec<_>>();
+
+    let mut word = [false; 196];
+    word.copy_from_slice(&code().encode(&bits));
+
+    word
+}
+
+/// Try to decode the given 196 interleaved bits in transmission order, alternating row
+/// and column passes until every row and column is a codeword.
+///
+/// If decoding converged, return `Some((data, err))`, where `data` is the 96-bit payload
+/// and `err` is the total number of corrected bits, not counting the reserved bits.
+/// Otherwise, return `None` to indicate an unrecoverable error.
+pub fn decode(bits: &[bool; 196]) -> Option<([u8; 12], usize)> {
+    let (bits, err) = code().decode(bits)?;
+    let mut data = [0; 12];
+
+    for (i, &bit) in bits[RESERVED..].iter().enumerate() {
+        data[i / 8] |= (bit as u8) << (7 - i % 8);
+    }
+
+    Some((data, err))
+}
+
+/// The BPTC (196, 96) code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bptc;
+
+impl Encoder for Bptc {
+    type Data = [u8; 12];
+    type Codeword = [bool; 196];
+
+    fn encode(&self, data: [u8; 12]) -> [bool; 196] { encode(&data) }
+}
+
+impl Decoder for Bptc {
+    fn decode(&self, word: [bool; 196]) -> Option<([u8; 12], usize)> { decode(&word) }
+}
+
+impl BlockCode for Bptc {
+    const N: usize = 196;
+    const K: usize = 96;
+    // Product of the row and column distances.
+    const D: usize = 9;
+}
+
+impl DynCode for Bptc {
+    fn name(&self) -> &'static str { "bptc196" }
+
+    fn data_len(&self) -> Option<usize> { Some(96) }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+        if data.len() != 96 {
+            return None;
+        }
+
+        let mut bytes = [0; 12];
+        to_symbols(data, 8, &mut bytes);
+
+        Some(encode(&bytes).to_vec())
+    }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        if word.len() != 196 {
+            return None;
+        }
+
+        let mut bits = [false; 196];
+        bits.copy_from_slice(word);
+
+        decode(&bits).map(|(data, err)| (from_symbols(&data, 8), err))
+    }
+}
+
+/// Interleave the given 196 bits, in matrix order with the leading reserved bit first,
+/// into transmission order.
+pub fn interleave(matrix: &[bool; 196]) -> [bool; 196] {
+    let mut bits = [false; 196];
+
+    for (i, &bit) in matrix.iter().enumerate() {
+        bits[position(i)] = bit;
+    }
+
+    bits
+}
+
+/// Deinterleave the given 196 bits, in transmission order, into matrix order with the
+/// leading reserved bit first.
+pub fn deinterleave(bits: &[bool; 196]) -> [bool; 196] {
+    let mut matrix = [false; 196];
+
+    for (i, bit) in matrix.iter_mut().enumerate() {
+        *bit = bits[position(i)];
+    }
+
+    matrix
+}
+
+/// Interleave the given 196 bits in place, as in [`interleave`](fn.interleave.html).
+pub fn interleave_in_place(bits: &mut [bool; 196]) {
+    for start in cycle_starts() {
+        // Carry each bit forward to its transmitted position around the cycle.
+        let mut carry = bits[start];
+        let mut i = position(start);
+
+        while i != start {
+            ::std::mem::swap(&mut carry, &mut bits[i]);
+            i = position(i);
+        }
+
+        bits[start] = carry;
+    }
+}
+
+/// Deinterleave the given 196 bits in place, as in
+/// [`deinterleave`](fn.deinterleave.html).
+pub fn deinterleave_in_place(bits: &mut [bool; 196]) {
+    for start in cycle_starts() {
+        // Pull each bit back from its transmitted position around the cycle.
+        let first = bits[start];
+        let mut i = start;
+
+        while position(i) != start {
+            bits[i] = bits[position(i)];
+            i = position(i);
+        }
+
+        bits[i] = first;
+    }
+}
+
+/// Iterate over the smallest index in each cycle of the interleaving permutation, so
+/// each cycle is visited once without any scratch memory.
+fn cycle_starts() -> impl Iterator<Item = usize> {
+    (0..196).filter(|&start| {
+        let mut i = position(start);
+
+        while i > start {
+            i = position(i);
+        }
+
+        i == start
+    })
+}
+
+/// Get the transmitted position of the matrix bit at the given index.
+fn position(i: usize) -> usize { i * 181 % 196 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hamming::{decode_13_9, decode_15_11};
+    use sim::{RandomSource, SplitMix64};
+
+    const ROWS: usize = 13;
+    const COLS: usize = 15;
+
+    /// Get the index of the matrix bit at the given row and column.
+    fn pos(r: usize, c: usize) -> usize { 1 + r * COLS + c }
+
+    /// Get the 15-bit word in the given row, with the first bit in the MSB.
+    fn row(matrix: &[bool; 196], r: usize) -> u16 {
+        (0..COLS).fold(0, |w, c| w << 1 | matrix[pos(r, c)] as u16)
+    }
+
+    /// Get the 13-bit word in the given column, with the first bit in the MSB.
+    fn col(matrix: &[bool; 196], c: usize) -> u16 {
+        (0..ROWS).fold(0, |w, r| w << 1 | matrix[pos(r, c)] as u16)
+    }
+
+    /// Get the 96 payload bits of the given matrix in transmission order.
+    fn payload_bits(matrix: &[bool; 196]) -> Vec<bool> {
+        (0..9).flat_map(|r| matrix[pos(r, 0)..pos(r, 11)].to_vec()).skip(RESERVED).collect()
+    }
+
+    fn random_payload(rng: &mut SplitMix64) -> [u8; 12] {
+        let mut data = [0; 12];
+
+        for b in data.iter_mut() {
+            *b = rng.next_u64() as u8;
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_interleave() {
+        let mut seen = [false; 196];
+
+        for i in 0..196 {
+            assert!(!seen[position(i)]);
+            seen[position(i)] = true;
+        }
+
+        assert_eq!(position(0), 0);
+        assert_eq!(position(1), 181);
+        assert_eq!(position(2), 166);
+
+        let mut rng = SplitMix64::new(2);
+        let mut matrix = [false; 196];
+
+        for bit in matrix.iter_mut() {
+            *bit = rng.next_u64() & 1 == 1;
+        }
+
+        let bits = interleave(&matrix);
+        assert_eq!(bits[181], matrix[1]);
+        assert_eq!(deinterleave(&bits)[..], matrix[..]);
+
+        let mut inplace = matrix;
+        interleave_in_place(&mut inplace);
+        assert_eq!(inplace[..], bits[..]);
+
+        deinterleave_in_place(&mut inplace);
+        assert_eq!(inplace[..], matrix[..]);
+
+        // Index 0 is a fixed point, so it forms its own cycle.
+        assert!(cycle_starts().any(|i| i == 0));
+        assert_eq!(position(195), 15);
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut rng = SplitMix64::new(5);
+
+        for _ in 0..20 {
+            let data = random_payload(&mut rng);
+            let matrix = deinterleave(&encode(&data));
+
+            // Every row and column is a codeword.
+            for r in 0..ROWS {
+                assert_eq!(decode_15_11(row(&matrix, r)).map(|(_, e)| e), Some(0));
+            }
+
+            for c in 0..COLS {
+                assert_eq!(decode_13_9(col(&matrix, c)).map(|(_, e)| e), Some(0));
+            }
+
+            // Reserved bits are zero and the payload is systematic.
+            assert!(!matrix[0] && !matrix[1] && !matrix[2] && !matrix[3]);
+
+            let payload = payload_bits(&matrix);
+            assert_eq!(payload.len(), 96);
+
+            for (i, &bit) in payload.iter().enumerate() {
+                assert_eq!(bit, data[i / 8] >> (7 - i % 8) & 1 == 1);
+            }
+
+            assert_eq!(decode(&encode(&data)), Some((data, 0)));
+        }
+
+        // The first payload bit follows the reserved bits in the first row.
+        let matrix = deinterleave(&encode(&[0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert!(matrix[4]);
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut rng = SplitMix64::new(17);
+
+        for _ in 0..50 {
+            let data = random_payload(&mut rng);
+            let mut bits = encode(&data);
+
+            // A single error anywhere in the matrix.
+            let i = 1 + (rng.next_u64() % 195) as usize;
+            bits[position(i)] ^= true;
+            assert_eq!(decode(&bits), Some((data, 1)));
+
+            // A full row and a full column wiped out, far beyond either code alone.
+            let mut bits = encode(&data);
+            let r = rng.next_u64() as usize % ROWS;
+            let c = rng.next_u64() as usize % COLS;
+
+            for i in 0..COLS {
+                bits[position(pos(r, i))] ^= true;
+            }
+
+            for i in 0..ROWS {
+                bits[position(pos(i, c))] ^= true;
+            }
+
+            assert_eq!(decode(&bits), Some((data, 26)));
+        }
+
+        // The leading reserved bit is ignored.
+        let data = random_payload(&mut rng);
+        let mut bits = encode(&data);
+        bits[0] = true;
+        assert_eq!(decode(&bits), Some((data, 0)));
+    }
+
+    #[test]
+    fn test_failure() {
+        let data = [0xa5; 12];
+        let mut bits = encode(&data);
+
+        // Two errors in each of two rows and two columns form a rectangle that neither
+        // code can see past, so decoding settles on a wrong codeword 5 bits further on, at
+        // the minimum distance from the sent one.
+        for &(r, c) in &[(2, 3), (2, 9), (6, 3), (6, 9)] {
+            bits[position(pos(r, c))] ^= true;
+        }
+
+        let wrong = [0xa5, 0xa5, 0xa7, 0xad, 0xa5, 0xa5, 0xa4, 0xa1, 0x85, 0x25, 0xa5, 0xa5];
+        assert_eq!(decode(&bits), Some((wrong, 5)));
+
+        let dist = encode(&wrong).iter().zip(&encode(&data)).filter(|&(a, b)| a != b).count();
+        assert_eq!(dist, Bptc::D);
+
+        // Heavy noise.
+        let mut bits = encode(&data);
+
+        for bit in bits.iter_mut().step_by(3) {
+            *bit = !*bit;
+        }
+
+        assert_ne!(decode(&bits).map(|(d, _)| d), Some(data));
+    }
+}
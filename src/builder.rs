@@ -0,0 +1,183 @@
+//! Bit-by-bit accumulation of received words.
+//!
+//! Demodulators usually deliver one bit at a time. A
+//! [`CodewordBuilder`](struct.CodewordBuilder.html) shifts them into a word of the
+//! right length for the chosen format and decodes it as soon as it's full, then starts
+//! over with the next word.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::builder::{BitOrder, CodewordBuilder, Format};
+//!
+//! let mut b = CodewordBuilder::new(Format::P25, BitOrder::MsbFirst);
+//! let word = cai_cyclic::encode_p25(0xa5) ^ 1 << 7;
+//!
+//! for i in (1..16).rev() {
+//!     assert_eq!(b.push_bit(word >> i & 1 == 1), None);
+//! }
+//!
+//! assert_eq!(b.push_bit(word & 1 == 1), Some(Some((0xa5, 1))));
+//! assert!(b.is_empty());
+//! ```
+
+/// Received word format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Base (17, 9, 5) code, decoded with [`decode`](../fn.decode.html).
+    Base,
+    /// DMR (16, 7, 6) code, decoded with [`decode_dmr`](../fn.decode_dmr.html).
+    Dmr,
+    /// P25 (16, 8, 5) code, decoded with [`decode_p25`](../fn.decode_p25.html).
+    P25,
+}
+
+impl Format {
+    /// Number of bits in a received word.
+    pub fn bits(self) -> u32 {
+        match self {
+            Format::Base => 17,
+            Format::Dmr | Format::P25 => 16,
+        }
+    }
+
+    /// Decode the given received word, widening the data to 16 bits.
+    fn decode(self, word: u32) -> Option<(u16, usize)> {
+        match self {
+            Format::Base => ::decode(word),
+            Format::Dmr => ::decode_dmr(word as u16).map(|(data, err)| (data as u16, err)),
+            Format::P25 => ::decode_p25(word as u16).map(|(data, err)| (data as u16, err)),
+        }
+    }
+}
+
+/// Order bits of a word arrive in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// MSB first, which is transmission order for DMR and P25.
+    MsbFirst,
+    /// LSB first.
+    LsbFirst,
+}
+
+/// Accumulates received bits into words and decodes each full word.
+#[derive(Copy, Clone, Debug)]
+pub struct CodewordBuilder {
+    /// Format of received words.
+    format: Format,
+    /// Order bits arrive in.
+    order: BitOrder,
+    /// Bits received so far for the current word, in their final positions.
+    word: u32,
+    /// Number of bits received so far for the current word.
+    len: u32,
+}
+
+impl CodewordBuilder {
+    /// Create a new empty builder for words of the given format arriving in the given
+    /// bit order.
+    pub fn new(format: Format, order: BitOrder) -> Self {
+        CodewordBuilder { format, order, word: 0, len: 0 }
+    }
+
+    /// Format of received words.
+    pub fn format(&self) -> Format { self.format }
+
+    /// Number of bits received so far for the current word.
+    pub fn len(&self) -> usize { self.len as usize }
+
+    /// Check if no bits have been received for the current word.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Discard any bits received for the current word.
+    pub fn reset(&mut self) {
+        self.word = 0;
+        self.len = 0;
+    }
+
+    /// Add the next received bit.
+    ///
+    /// Return `None` while the current word is incomplete. Once the bit completes it,
+    /// return `Some` with the decoding result for the word's format, with the data
+    /// widened to 16 bits, and start a new word.
+    pub fn push_bit(&mut self, bit: bool) -> Option<Option<(u16, usize)>> {
+        let n = self.format.bits();
+
+        self.word = match self.order {
+            BitOrder::MsbFirst => self.word << 1 | bit as u32,
+            BitOrder::LsbFirst => self.word | (bit as u32) << self.len,
+        };
+
+        self.len += 1;
+
+        if self.len < n {
+            return None;
+        }
+
+        let word = self.word;
+        self.reset();
+
+        Some(self.format.decode(word))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {encode, encode_dmr, decode};
+
+    /// Push the given word's bits in the given order and collect the result.
+    fn push_word(b: &mut CodewordBuilder, word: u32) -> Option<(u16, usize)> {
+        let n = b.format().bits();
+
+        let bits = (0..n).map(|i| match b.order {
+            BitOrder::MsbFirst => word >> (n - 1 - i) & 1 == 1,
+            BitOrder::LsbFirst => word >> i & 1 == 1,
+        }).collect::<Vec<_>>();
+
+        let (last, rest) = bits.split_last().unwrap();
+
+        for &bit in rest {
+            assert_eq!(b.push_bit(bit), None);
+        }
+
+        b.push_bit(*last).unwrap()
+    }
+
+    #[test]
+    fn test_base() {
+        for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            let mut b = CodewordBuilder::new(Format::Base, order);
+
+            for w in (0..1 << 17).step_by(101) {
+                assert_eq!(push_word(&mut b, w), decode(w));
+                assert!(b.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_shortened() {
+        let mut b = CodewordBuilder::new(Format::Dmr, BitOrder::LsbFirst);
+        assert_eq!(push_word(&mut b, encode_dmr(0x55) as u32 ^ 0b11 << 4), Some((0x55, 2)));
+
+        let mut b = CodewordBuilder::new(Format::P25, BitOrder::MsbFirst);
+        assert_eq!(push_word(&mut b, encode(0xff) & 0xffff), Some((0xff, 0)));
+        assert_eq!(push_word(&mut b, 0x1f), None);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut b = CodewordBuilder::new(Format::Base, BitOrder::MsbFirst);
+
+        for _ in 0..5 {
+            b.push_bit(true);
+        }
+
+        assert_eq!(b.len(), 5);
+        b.reset();
+        assert!(b.is_empty());
+
+        assert_eq!(push_word(&mut b, encode(0b110011001)), Some((0b110011001, 0)));
+    }
+}
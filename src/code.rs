@@ -0,0 +1,286 @@
+//! Generic encoding and decoding traits implemented by every code in the crate.
+//!
+//! The free functions in each module remain the primary interface. These traits let
+//! generic framing or modem code swap one code for another without adapters.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::Cyclic;
+//! use cai_cyclic::code::{Encoder, Decoder};
+//!
+//! fn loopback<C: Decoder>(code: &C, data: C::Data) -> Option<(C::Data, usize)> {
+//!     code.decode(code.encode(data))
+//! }
+//!
+//! assert_eq!(loopback(&Cyclic, 0b101010101), Some((0b101010101, 0)));
+//! ```
+//!
+//! Block codes also implement [`BlockCode`](trait.BlockCode.html), which gives their
+//! parameters as associated constants:
+//!
+//! ```rust
+//! use cai_cyclic::Cyclic;
+//! use cai_cyclic::code::BlockCode;
+//!
+//! fn correctable<C: BlockCode>(_: &C) -> usize { (C::D - 1) / 2 }
+//!
+//! assert_eq!((Cyclic::N, Cyclic::K, correctable(&Cyclic)), (17, 9, 2));
+//! ```
+//!
+//! The associated types make these traits unusable as trait objects, so every code also
+//! implements the object-safe [`DynCode`](trait.DynCode.html), which works on bit
+//! vectors. This lets multi-protocol applications choose the code for each channel at
+//! runtime:
+//!
+//! ```rust
+//! # #[cfg(feature = "pocsag")] {
+//! use cai_cyclic::{Cyclic, pocsag::Pocsag};
+//! use cai_cyclic::code::DynCode;
+//!
+//! let codes: Vec<Box<dyn DynCode>> = vec![Box::new(Cyclic), Box::new(Pocsag)];
+//!
+//! for code in &codes {
+//!     let data = vec![true; code.data_len().unwrap()];
+//!     let mut word = code.encode(&data).unwrap();
+//!     word[3] = !word[3];
+//!
+//!     assert_eq!(code.decode(&word), Some((data, 1)));
+//! }
+//! # }
+//! ```
+
+/// Encodes data words into codewords.
+pub trait Encoder {
+    /// Type of data words.
+    type Data;
+    /// Type of codewords.
+    type Codeword;
+
+    /// Encode the given data word into a codeword.
+    fn encode(&self, data: Self::Data) -> Self::Codeword;
+}
+
+/// Decodes received words back into data words.
+pub trait Decoder: Encoder {
+    /// Try to decode the given received word to the nearest codeword.
+    ///
+    /// If decoding was successful, return `Some((data, err))`, where `data` is the
+    /// recovered data word and `err` is the number of corrected bits. Otherwise, return
+    /// `None` to indicate an unrecoverable error.
+    fn decode(&self, word: Self::Codeword) -> Option<(Self::Data, usize)>;
+}
+
+/// Block code with fixed length, dimension, and minimum distance, so frame assemblers
+/// can size and protect fields generically.
+pub trait BlockCode: Decoder {
+    /// Number of bits in a codeword.
+    const N: usize;
+    /// Number of data bits in a codeword.
+    const K: usize;
+    /// Minimum distance between codewords.
+    const D: usize;
+}
+
+/// Object-safe interface to any code, with data and codewords as bit vectors.
+///
+/// Bits are in transmission order, so block code words are MSB first.
+pub trait DynCode {
+    /// Short name of the code.
+    fn name(&self) -> &'static str;
+
+    /// Number of bits in a data word, or `None` if it can vary.
+    fn data_len(&self) -> Option<usize>;
+
+    /// Encode the given data bits, or return `None` if there are the wrong number of
+    /// them.
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>>;
+
+    /// Try to decode the given received bits, with results as for
+    /// [`Decoder::decode`](trait.Decoder.html#tymethod.decode). Return `None` if there
+    /// are the wrong number of bits.
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)>;
+}
+
+/// Convert the given bits, MSB first, into an integer.
+pub(crate) fn from_bits(bits: &[bool]) -> u64 {
+    assert!(bits.len() <= 64);
+    bits.iter().fold(0, |x, &b| x << 1 | b as u64)
+}
+
+/// Convert the given number of low bits of the given integer into bits, MSB first.
+pub(crate) fn to_bits(x: u64, len: usize) -> Vec<bool> {
+    (0..len).rev().map(|i| x >> i & 1 == 1).collect()
+}
+
+/// Convert the given bits, MSB first, into symbols of the given width, such as bytes or
+/// Reed-Solomon hexbits.
+#[cfg(any(feature = "hamming", feature = "reed-solomon", feature = "trellis"))]
+pub(crate) fn to_symbols(bits: &[bool], width: usize, symbols: &mut [u8]) {
+    assert!(width <= 8);
+    assert_eq!(bits.len(), symbols.len() * width);
+
+    for (sym, chunk) in symbols.iter_mut().zip(bits.chunks(width)) {
+        *sym = from_bits(chunk) as u8;
+    }
+}
+
+/// Convert the given symbols of the given width into bits, MSB first.
+#[cfg(any(feature = "hamming", feature = "reed-solomon", feature = "trellis"))]
+pub(crate) fn from_symbols(symbols: &[u8], width: usize) -> Vec<bool> {
+    symbols.iter().flat_map(|&sym| to_bits(sym as u64, width)).collect()
+}
+
+/// Implement `DynCode` for an unsigned-integer block code with the given data and
+/// codeword lengths.
+macro_rules! impl_dyn_block_code {
+    ($code:ty, $name:expr, $data:ty, $k:expr, $word:ty, $n:expr) => {
+        impl ::code::DynCode for $code {
+            fn name(&self) -> &'static str { $name }
+
+            fn data_len(&self) -> Option<usize> { Some($k) }
+
+            fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+                if data.len() != $k {
+                    return None;
+                }
+
+                let word = ::code::Encoder::encode(self, ::code::from_bits(data) as $data);
+                Some(::code::to_bits(word as u64, $n))
+            }
+
+            fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+                if word.len() != $n {
+                    return None;
+                }
+
+                ::code::Decoder::decode(self, ::code::from_bits(word) as $word)
+                    .map(|(data, err)| (::code::to_bits(data as u64, $k), err))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Cyclic;
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(to_bits(0b1011, 4), vec![true, false, true, true]);
+        assert_eq!(to_bits(0b1011, 6), vec![false, false, true, false, true, true]);
+        assert_eq!(from_bits(&to_bits(0x1abcd, 17)), 0x1abcd);
+        assert_eq!(from_bits(&[]), 0);
+    }
+
+    #[cfg(any(feature = "hamming", feature = "reed-solomon", feature = "trellis"))]
+    #[test]
+    fn test_symbols() {
+        let mut syms = [0; 3];
+        to_symbols(&to_bits(0b101010_000001_111110, 18), 6, &mut syms);
+        assert_eq!(syms, [0b101010, 0b000001, 0b111110]);
+        assert_eq!(from_symbols(&syms, 6), to_bits(0b101010_000001_111110, 18));
+        assert_eq!(from_symbols(&[0x80, 0x01], 8), to_bits(0x8001, 16));
+    }
+
+    #[test]
+    fn test_block_code() {
+        fn params<C: BlockCode>(_: &C) -> (usize, usize, usize) { (C::N, C::K, C::D) }
+
+        assert_eq!(params(&Cyclic), (17, 9, 5));
+        #[cfg(feature = "bch")]
+        assert_eq!(params(&::bch::Bch), (63, 16, 23));
+        #[cfg(feature = "golay")]
+        assert_eq!(params(&::golay::Golay), (23, 12, 7));
+        #[cfg(feature = "golay")]
+        assert_eq!(params(&::golay::ExtendedGolay), (24, 12, 8));
+        #[cfg(feature = "hamming")]
+        assert_eq!(params(&::hamming::Hamming15), (15, 11, 3));
+        #[cfg(feature = "hamming")]
+        assert_eq!(params(&::hamming::Hamming13), (13, 9, 3));
+        #[cfg(feature = "hamming")]
+        assert_eq!(params(&::hamming::Hamming10), (10, 6, 3));
+        #[cfg(feature = "hamming")]
+        assert_eq!(params(&::bptc::Bptc), (196, 96, 9));
+        #[cfg(feature = "pocsag")]
+        assert_eq!(params(&::pocsag::Pocsag), (32, 21, 6));
+    }
+
+    #[test]
+    fn test_dyn_code() {
+        let codes: Vec<Box<dyn DynCode>> = vec![
+            Box::new(Cyclic),
+            #[cfg(feature = "bch")]
+            Box::new(::bch::Bch),
+            #[cfg(feature = "golay")]
+            Box::new(::golay::Golay),
+            #[cfg(feature = "golay")]
+            Box::new(::golay::ExtendedGolay),
+            #[cfg(feature = "hamming")]
+            Box::new(::hamming::Hamming15),
+            #[cfg(feature = "hamming")]
+            Box::new(::hamming::Hamming13),
+            #[cfg(feature = "hamming")]
+            Box::new(::hamming::Hamming10),
+            #[cfg(feature = "hamming")]
+            Box::new(::bptc::Bptc),
+            #[cfg(feature = "reed-solomon")]
+            Box::new(::reed_solomon::Rs24_12),
+            #[cfg(feature = "reed-solomon")]
+            Box::new(::reed_solomon::Rs24_16),
+            #[cfg(feature = "reed-solomon")]
+            Box::new(::reed_solomon::Rs36_20),
+            #[cfg(feature = "reed-solomon")]
+            Box::new(::reed_solomon::Rs12_9),
+            #[cfg(feature = "trellis")]
+            Box::new(::trellis::Trellis34),
+            #[cfg(feature = "trellis")]
+            Box::new(::trellis::Trellis12),
+            #[cfg(feature = "pocsag")]
+            Box::new(::pocsag::Pocsag),
+            #[cfg(feature = "convolutional")]
+            Box::new(::convolutional::Code::new(3, &[0b111, 0b101])),
+        ];
+
+        for code in &codes {
+            match code.name() {
+                "cyclic17" => assert_eq!(code.data_len(), Some(9)),
+                "bch63" => assert_eq!(code.data_len(), Some(16)),
+                "golay23" | "golay24" => assert_eq!(code.data_len(), Some(12)),
+                "hamming15" => assert_eq!(code.data_len(), Some(11)),
+                "hamming13" => assert_eq!(code.data_len(), Some(9)),
+                "hamming10" => assert_eq!(code.data_len(), Some(6)),
+                "bptc196" => assert_eq!(code.data_len(), Some(96)),
+                "rs24_12" => assert_eq!(code.data_len(), Some(72)),
+                "rs24_16" => assert_eq!(code.data_len(), Some(96)),
+                "rs36_20" => assert_eq!(code.data_len(), Some(120)),
+                "rs12_9" => assert_eq!(code.data_len(), Some(72)),
+                "trellis34" => assert_eq!(code.data_len(), Some(144)),
+                "trellis12" => assert_eq!(code.data_len(), Some(96)),
+                "pocsag32" => assert_eq!(code.data_len(), Some(21)),
+                "convolutional" => assert_eq!(code.data_len(), None),
+                _ => panic!(),
+            }
+        }
+
+        for code in &codes {
+            let data = (0..code.data_len().unwrap_or(12)).map(|i| i % 3 == 0)
+                .collect::<Vec<_>>();
+            let mut word = code.encode(&data).unwrap();
+
+            assert_eq!(code.decode(&word), Some((data.clone(), 0)));
+
+            // The first bit of the BPTC is reserved rather than coded, so flip the second.
+            word[1] = !word[1];
+            assert_eq!(code.decode(&word), Some((data, 1)));
+        }
+
+        assert_eq!(codes[0].encode(&[true; 8]), None);
+        assert_eq!(codes[0].decode(&[true; 16]), None);
+        assert_eq!(codes[0].decode(&[true; 18]), None);
+
+        assert_eq!(codes[0].encode(&to_bits(0b110011001, 9)),
+                   Some(to_bits(::encode(0b110011001) as u64, 17)));
+    }
+}
@@ -0,0 +1,317 @@
+//! Configurable convolutional codes with hard- and soft-decision Viterbi decoding.
+//!
+//! A code is described by its constraint length K and a set of rate 1/n generator
+//! polynomials, given in the usual octal convention where the MSB of each polynomial
+//! taps the current input bit. Encoding is always terminated with K - 1 zero tail bits,
+//! so the decoder can trace back from the all-zero state.
+//!
+//! An optional puncturing pattern can be used to raise the code rate. The pattern is
+//! applied cyclically over the output stream, with `false` entries deleting the
+//! corresponding bit, and punctured bits are treated as erasures when decoding.
+//!
+//! ## Soft values
+//!
+//! Soft inputs are log-likelihood ratios, where positive values favor a 0 bit, negative
+//! values favor a 1 bit, and 0 is an erasure.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::convolutional::Code;
+//!
+//! // The K = 3 (7, 5) code.
+//! let code = Code::new(3, &[0o7, 0o5]);
+//!
+//! let data = [true, false, true, true];
+//! let mut bits = code.encode(&data);
+//! bits[3] = !bits[3];
+//!
+//! assert_eq!(code.decode(&bits), Some((data.to_vec(), 1)));
+//! ```
+
+use code::{DynCode, Encoder, Decoder};
+
+/// A terminated convolutional code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Code {
+    /// Constraint length K.
+    constraint: usize,
+    /// Generator polynomials, one per output bit.
+    polys: Vec<u32>,
+    /// Cyclic puncturing pattern over the output bits.
+    puncture: Vec<bool>,
+}
+
+impl Code {
+    /// Create a new unpunctured code with the given constraint length and generator
+    /// polynomials.
+    pub fn new(constraint: usize, polys: &[u32]) -> Self {
+        assert!((2..=16).contains(&constraint));
+        assert!(!polys.is_empty());
+
+        for &poly in polys {
+            assert!(poly != 0 && poly >> constraint == 0);
+        }
+
+        Code {
+            constraint,
+            polys: polys.to_vec(),
+            puncture: vec![true],
+        }
+    }
+
+    /// Puncture the code output with the given cyclic pattern, where `false` deletes the
+    /// corresponding output bit.
+    pub fn punctured(self, pattern: &[bool]) -> Self {
+        assert!(pattern.iter().any(|&keep| keep));
+
+        Code {
+            puncture: pattern.to_vec(),
+            ..self
+        }
+    }
+
+    /// Constraint length of the code.
+    pub fn constraint(&self) -> usize { self.constraint }
+
+    /// Number of output bits per input bit, before puncturing.
+    pub fn outputs(&self) -> usize { self.polys.len() }
+
+    /// Encode the given data bits, including the zero tail, and puncture the result.
+    pub fn encode(&self, data: &[bool]) -> Vec<bool> {
+        let tail = self.constraint - 1;
+
+        let raw = data.iter().cloned()
+            .chain((0..tail).map(|_| false))
+            .scan(0, |state, bit| {
+                let reg = (bit as u32) << tail | *state;
+                *state = reg >> 1;
+                Some(self.outputs_for(reg))
+            })
+            .flat_map(|out| self.polys.iter().enumerate().map(move |(i, _)| {
+                out >> i & 1 == 1
+            }))
+            .collect::<Vec<_>>();
+
+        raw.into_iter()
+            .zip(self.puncture.iter().cycle())
+            .filter(|&(_, &keep)| keep)
+            .map(|(bit, _)| bit)
+            .collect()
+    }
+
+    /// Decode the given hard-decision bits with the Viterbi algorithm.
+    ///
+    /// If the input length is consistent with the code, return `Some((data, err))`,
+    /// where `data` are the decoded data bits and `err` is the number of received bits
+    /// that differ from the re-encoded path. Otherwise, return `None`.
+    pub fn decode(&self, bits: &[bool]) -> Option<(Vec<bool>, usize)> {
+        let soft = bits.iter().map(|&b| if b { -1 } else { 1 }).collect::<Vec<_>>();
+        let data = self.decode_soft(&soft)?;
+
+        let err = self.encode(&data).iter().zip(bits.iter())
+            .filter(|&(a, b)| a != b)
+            .count();
+
+        Some((data, err))
+    }
+
+    /// Decode the given soft-decision bits with the Viterbi algorithm.
+    ///
+    /// Return `None` if the input length isn't consistent with the code.
+    pub fn decode_soft(&self, soft: &[i8]) -> Option<Vec<bool>> {
+        let raw = self.depuncture(soft)?;
+        let n = self.polys.len();
+        let tail = self.constraint - 1;
+        let states = 1usize << tail;
+        let steps = raw.len() / n;
+
+        let mut metrics = vec![i32::MIN; states];
+        let mut next = vec![i32::MIN; states];
+        // For each step and destination state, the input bit and source state.
+        let mut paths = vec![0u32; steps * states];
+
+        metrics[0] = 0;
+
+        for (step, symbol) in raw.chunks(n).enumerate() {
+            for m in next.iter_mut() {
+                *m = i32::MIN;
+            }
+
+            for (state, &metric) in metrics.iter().enumerate() {
+                if metric == i32::MIN {
+                    continue;
+                }
+
+                for bit in 0..2 {
+                    let reg = (bit as u32) << tail | state as u32;
+                    let dest = (reg >> 1) as usize;
+                    let cand = metric + self.branch_metric(reg, symbol);
+
+                    if cand > next[dest] {
+                        next[dest] = cand;
+                        paths[step * states + dest] = reg;
+                    }
+                }
+            }
+
+            ::std::mem::swap(&mut metrics, &mut next);
+        }
+
+        let mut data = (0..steps).rev()
+            .scan(0usize, |state, step| {
+                let reg = paths[step * states + *state];
+                *state = (reg & (states as u32 - 1)) as usize;
+                Some(reg >> tail & 1 == 1)
+            })
+            .collect::<Vec<_>>();
+
+        data.reverse();
+        data.truncate(steps - tail);
+
+        Some(data)
+    }
+
+    /// Compute the output bits for the given shift register contents, with the output
+    /// of the first polynomial in the LSB.
+    fn outputs_for(&self, reg: u32) -> u32 {
+        self.polys.iter().enumerate().fold(0, |out, (i, &poly)| {
+            out | ((reg & poly).count_ones() & 1) << i
+        })
+    }
+
+    /// Compute the correlation between the expected outputs for the given shift register
+    /// contents and the received soft symbol.
+    fn branch_metric(&self, reg: u32, symbol: &[i8]) -> i32 {
+        let out = self.outputs_for(reg);
+
+        symbol.iter().enumerate().map(|(i, &s)| {
+            if out >> i & 1 == 1 { -(s as i32) } else { s as i32 }
+        }).sum()
+    }
+
+    /// Reinsert erasures at the punctured positions of the given received bits.
+    fn depuncture(&self, soft: &[i8]) -> Option<Vec<i8>> {
+        let n = self.polys.len();
+        let tail = self.constraint - 1;
+        let mut raw = Vec::new();
+        let mut rx = soft.iter();
+        let mut pattern = self.puncture.iter().cycle();
+
+        // Consume received bits until they run out, then finish the current symbol.
+        loop {
+            if raw.len() % n == 0 && rx.len() == 0 {
+                break;
+            }
+
+            if *pattern.next().unwrap() {
+                raw.push(*rx.next()?);
+            } else {
+                raw.push(0);
+            }
+        }
+
+        if raw.len() / n < tail {
+            return None;
+        }
+
+        Some(raw)
+    }
+}
+
+impl Encoder for Code {
+    type Data = Vec<bool>;
+    type Codeword = Vec<bool>;
+
+    fn encode(&self, data: Vec<bool>) -> Vec<bool> { Code::encode(self, &data) }
+}
+
+impl Decoder for Code {
+    fn decode(&self, word: Vec<bool>) -> Option<(Vec<bool>, usize)> {
+        Code::decode(self, &word)
+    }
+}
+
+impl DynCode for Code {
+    fn name(&self) -> &'static str { "convolutional" }
+
+    fn data_len(&self) -> Option<usize> { None }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> { Some(Code::encode(self, data)) }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> { Code::decode(self, word) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bits(s: &str) -> Vec<bool> {
+        s.chars().map(|c| c == '1').collect()
+    }
+
+    #[test]
+    fn test_encode() {
+        let c = Code::new(3, &[0o7, 0o5]);
+        assert_eq!(c.encode(&bits("1011")), bits("111000010111"));
+        assert_eq!(c.encode(&[]), bits("0000"));
+
+        let c = c.punctured(&[true, true, true, false]);
+        assert_eq!(c.encode(&bits("1011")), bits("111000011"));
+    }
+
+    #[test]
+    fn test_decode() {
+        let c = Code::new(7, &[0o171, 0o133]);
+        let data = bits("1101001110001011110100101");
+        let enc = c.encode(&data);
+
+        assert_eq!(enc.len(), (data.len() + 6) * 2);
+        assert_eq!(c.decode(&enc), Some((data.clone(), 0)));
+
+        // Spread-out errors are all corrected.
+        let mut rx = enc.clone();
+        for i in (3..rx.len()).step_by(11) {
+            rx[i] = !rx[i];
+        }
+        assert_eq!(c.decode(&rx), Some((data.clone(), 6)));
+
+        // Too short to hold the tail.
+        assert_eq!(c.decode(&enc[..8]), None);
+    }
+
+    #[test]
+    fn test_decode_punctured() {
+        let c = Code::new(7, &[0o171, 0o133])
+            .punctured(&[true, true, true, false, false, true]);
+
+        let data = bits("110100111000101111010010");
+        let enc = c.encode(&data);
+
+        assert_eq!(enc.len(), (data.len() + 6) * 4 / 3);
+        assert_eq!(c.decode(&enc), Some((data.clone(), 0)));
+
+        let mut rx = enc.clone();
+        rx[5] = !rx[5];
+        rx[25] = !rx[25];
+        assert_eq!(c.decode(&rx), Some((data.clone(), 2)));
+    }
+
+    #[test]
+    fn test_decode_soft() {
+        let c = Code::new(5, &[0o23, 0o35]);
+        let data = bits("0110111010010001");
+
+        let mut soft = c.encode(&data).iter()
+            .map(|&b| if b { -100 } else { 100 })
+            .collect::<Vec<i8>>();
+
+        // Weak wrong decisions and erasures are outvoted by the strong ones.
+        for i in (0..soft.len()).step_by(4) {
+            soft[i] = -soft[i] / 10;
+        }
+        soft[1] = 0;
+
+        assert_eq!(c.decode_soft(&soft), Some(data));
+    }
+}
@@ -0,0 +1,192 @@
+//! Checksums paired with the error correcting codes in DMR and P25.
+//!
+//! The CRCs all process bits MSB first from an all-zero register, matching the usual air
+//! interface definitions. The outputs of all but CRC-8 are inverted:
+//!
+//! - [`crc_ccitt`](fn.crc_ccitt.html): CRC-CCITT, x<sup>16</sup> + x<sup>12</sup> +
+//!   x<sup>5</sup> + 1, protecting DMR CSBKs and data headers and P25 TSBKs and packet
+//!   headers.
+//! - [`crc9`](fn.crc9.html): CRC-9, x<sup>9</sup> + x<sup>6</sup> + x<sup>4</sup> +
+//!   x<sup>3</sup> + 1, protecting each block of DMR confirmed data.
+//! - [`crc32`](fn.crc32.html): CRC-32 with the Ethernet polynomial, protecting complete
+//!   DMR and P25 packets.
+//! - [`crc8`](fn.crc8.html): CRC-8, x<sup>8</sup> + x<sup>2</sup> + x + 1, protecting
+//!   DMR short link control. ETSI TS 102 361-1 section B.3.7 doesn't invert this one.
+//!
+//! DMR additionally XORs most CRCs with a mask chosen by the burst's data type, so a
+//! payload can't be accepted from the wrong kind of burst. The CRC-CCITT, CRC-9, and
+//! CRC-8 functions take the mask as a parameter, with 0 for no mask, and the common DMR
+//! masks are given as constants.
+//!
+//! Embedded link control instead uses a 5-bit checksum, computed by
+//! [`checksum5`](fn.checksum5.html).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::crc;
+//!
+//! assert_eq!(crc::crc_ccitt(b"123456789", 0), 0xce3c);
+//!
+//! let csbk = [0x3d, 0x00, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+//! let crc = crc::crc_ccitt(&csbk, crc::CSBK_MASK);
+//! assert_eq!(crc ^ crc::CSBK_MASK, crc::crc_ccitt(&csbk, 0));
+//!
+//! let lc = [0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x56, 0x78];
+//! assert_eq!(crc::checksum5(&lc), ((0x12 + 0x34 + 0x56 + 0x78) % 31) as u8);
+//! ```
+
+/// CRC-CCITT mask for DMR privacy indicator headers.
+pub const PI_HEADER_MASK: u16 = 0x6969;
+
+/// CRC-CCITT mask for DMR control signalling blocks.
+pub const CSBK_MASK: u16 = 0xa5a5;
+
+/// CRC-CCITT mask for DMR multiple block control headers.
+pub const MBC_HEADER_MASK: u16 = 0xaaaa;
+
+/// CRC-CCITT mask for DMR data headers.
+pub const DATA_HEADER_MASK: u16 = 0xcccc;
+
+/// CRC-9 mask for DMR rate 1/2 confirmed data blocks.
+pub const RATE_1_2_DATA_MASK: u16 = 0x0f0;
+
+/// CRC-9 mask for DMR rate 3/4 confirmed data blocks.
+pub const RATE_3_4_DATA_MASK: u16 = 0x1ff;
+
+/// Compute the CRC-CCITT of the given bytes, XORed with the given mask.
+pub fn crc_ccitt(data: &[u8], mask: u16) -> u16 {
+    !crc(data, 16, 0x1021) as u16 ^ mask
+}
+
+/// Compute the CRC-9 of the given bytes, XORed with the given 9-bit mask.
+pub fn crc9(data: &[u8], mask: u16) -> u16 {
+    assert_eq!(mask >> 9, 0);
+    (!crc(data, 9, 0x059) as u16 & 0x1ff) ^ mask
+}
+
+/// Compute the CRC-32 of the given bytes.
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc(data, 32, 0x04c11db7)
+}
+
+/// Compute the CRC-8 of the given bytes, XORed with the given mask.
+///
+/// The 28 bits of a short LC message aren't a whole number of bytes, but leading zero
+/// bits don't change the CRC, so they can be given right-aligned in 4 bytes.
+pub fn crc8(data: &[u8], mask: u8) -> u8 {
+    crc(data, 8, 0x07) as u8 ^ mask
+}
+
+/// Compute the 5-bit checksum of the given bytes: their sum modulo 31.
+pub fn checksum5(data: &[u8]) -> u8 {
+    (data.iter().map(|&b| b as u32).sum::<u32>() % 31) as u8
+}
+
+/// Compute the width-bit CRC of the given bytes with the given polynomial, without the
+/// x<sup>width</sup> term, from an all-zero register and without inverting the result.
+fn crc(data: &[u8], width: u32, poly: u32) -> u32 {
+    let top = 1 << (width - 1);
+    let mask = u32::MAX >> (32 - width);
+
+    data.iter().fold(0u32, |reg, &byte| {
+        (0..8).rev().fold(reg, |reg, i| {
+            let feedback = (reg & top != 0) != (byte >> i & 1 == 1);
+            let reg = reg << 1 & mask;
+
+            if feedback { reg ^ poly } else { reg }
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_values() {
+        // Standard check values over "123456789": CRC-16/GSM, CRC-32/CKSUM without the
+        // length, and CRC-8/SMBUS.
+        assert_eq!(crc_ccitt(b"123456789", 0), 0xce3c);
+        assert_eq!(crc32(b"123456789"), 0x765e7680);
+        assert_eq!(crc8(b"123456789", 0), 0xf4);
+
+        assert_eq!(crc_ccitt(&[], 0), 0xffff);
+        assert_eq!(crc9(&[], 0), 0x1ff);
+        assert_eq!(crc32(&[]), 0xffffffff);
+        assert_eq!(crc8(&[], 0), 0);
+    }
+
+    #[test]
+    fn test_short_lc() {
+        // The null short LC message, SLCO 0 with all-zero data, is sent with a zero CRC.
+        assert_eq!(crc8(&[0x00, 0x00, 0x00, 0x00], 0), 0);
+
+        // Leading zero bits don't change the CRC, so the 28-bit message can be padded.
+        let slc = [0x01, 0x23, 0x45, 0x67];
+        assert_eq!(crc8(&slc, 0), crc8(&[0x00, 0x00, 0x00, 0x00, 0x01, 0x23, 0x45, 0x67], 0));
+        assert_ne!(crc8(&slc, 0), crc8(&[0x12, 0x34, 0x56, 0x70], 0));
+    }
+
+    #[test]
+    fn test_masks() {
+        let data = [0x12, 0x34, 0x56, 0x78, 0x9a];
+
+        for &mask in &[PI_HEADER_MASK, CSBK_MASK, MBC_HEADER_MASK, DATA_HEADER_MASK] {
+            assert_eq!(crc_ccitt(&data, mask), crc_ccitt(&data, 0) ^ mask);
+        }
+
+        for &mask in &[RATE_1_2_DATA_MASK, RATE_3_4_DATA_MASK] {
+            assert_eq!(crc9(&data, mask), crc9(&data, 0) ^ mask);
+        }
+
+        assert_eq!(crc8(&data, 0x96), crc8(&data, 0) ^ 0x96);
+    }
+
+    #[test]
+    fn test_crc9() {
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        let base = crc9(&data, 0);
+        assert_eq!(base >> 9, 0);
+
+        // Every single-bit and double-bit error changes the CRC.
+
+        for i in 0..40 {
+            for j in i..40 {
+                let mut bad = data;
+                bad[i / 8] ^= 0x80 >> (i % 8);
+
+                if j != i {
+                    bad[j / 8] ^= 0x80 >> (j % 8);
+                }
+
+                assert_ne!(crc9(&bad, 0), base);
+            }
+        }
+    }
+
+    #[test]
+    fn test_residue() {
+        // The uninverted CRC appended to the data leaves a zero register.
+        let data = b"DMR and P25";
+
+        let mut msg = data.to_vec();
+        msg.extend_from_slice(&(!crc_ccitt(data, 0)).to_be_bytes());
+        assert_eq!(crc(&msg, 16, 0x1021), 0);
+
+        let mut msg = data.to_vec();
+        msg.extend_from_slice(&(!crc32(data)).to_be_bytes());
+        assert_eq!(crc(&msg, 32, 0x04c11db7), 0);
+
+        let mut msg = data.to_vec();
+        msg.push(crc8(data, 0));
+        assert_eq!(crc(&msg, 8, 0x07), 0);
+    }
+
+    #[test]
+    fn test_checksum5() {
+        assert_eq!(checksum5(&[]), 0);
+        assert_eq!(checksum5(&[31]), 0);
+        assert_eq!(checksum5(&[0xff; 9]), (0xff * 9 % 31) as u8);
+    }
+}
@@ -0,0 +1,174 @@
+//! Per-word decode diagnostics exported as JSON lines.
+//!
+//! Each decoded word produces one [`Diagnostic`](struct.Diagnostic.html) record with the
+//! received word, its syndrome, the applied correction, the decoder backend, and the
+//! decode time. Records are written one JSON object per line, so they can be loaded
+//! directly by analysis tools without any custom logging code.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::diag::JsonLines;
+//!
+//! let mut log = JsonLines::new(Vec::new(), "scalar", cai_cyclic::decode);
+//!
+//! let w = cai_cyclic::encode(0b101010101) ^ 0b100;
+//! assert_eq!(log.decode(w).unwrap(), Some((0b101010101, 1)));
+//!
+//! let out = String::from_utf8(log.into_inner()).unwrap();
+//! assert!(out.contains("\"errors\":1,\"correction\":4,\"backend\":\"scalar\","));
+//! ```
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+use {encode, syndrome};
+
+/// Decode diagnostics for a single received word.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Received 17-bit word.
+    pub word: u32,
+    /// Syndrome of the received word.
+    pub syndrome: u8,
+    /// Decoder result, as `Some((data, err))` or `None`.
+    pub result: Option<(u16, usize)>,
+    /// Name of the decoder backend that produced the result.
+    pub backend: &'static str,
+    /// Wall-clock decode time in nanoseconds.
+    pub nanos: u64,
+}
+
+impl Diagnostic {
+    /// Decode the given word with the given decoder, recording the result and timing.
+    pub fn capture<F>(backend: &'static str, mut decoder: F, word: u32) -> Self where
+        F: FnMut(u32) -> Option<(u16, usize)>
+    {
+        let start = Instant::now();
+        let result = decoder(word);
+        let elapsed = start.elapsed();
+
+        Diagnostic {
+            word,
+            syndrome: syndrome(word),
+            result,
+            backend,
+            nanos: elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64,
+        }
+    }
+
+    /// Bits flipped by the decoder, or `None` if decoding failed.
+    pub fn correction(&self) -> Option<u32> {
+        self.result.map(|(data, _)| encode(data) ^ self.word)
+    }
+
+    /// Write the record as a single line of JSON, including the trailing newline.
+    ///
+    /// The `data`, `errors`, and `correction` fields are `null` when decoding failed.
+    pub fn write_json<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "{{\"word\":{},\"syndrome\":{},", self.word, self.syndrome)?;
+
+        match (self.result, self.correction()) {
+            (Some((data, err)), Some(fix)) => write!(w,
+                "\"data\":{},\"errors\":{},\"correction\":{},", data, err, fix)?,
+            _ => w.write_all(b"\"data\":null,\"errors\":null,\"correction\":null,")?,
+        }
+
+        w.write_all(b"\"backend\":")?;
+        write_string(&mut w, self.backend)?;
+        writeln!(w, ",\"nanos\":{}}}", self.nanos)
+    }
+}
+
+/// Decoder wrapper that logs a JSON line for every decoded word.
+#[derive(Debug)]
+pub struct JsonLines<W, F> {
+    out: W,
+    backend: &'static str,
+    decoder: F,
+}
+
+impl<W, F> JsonLines<W, F> where
+    W: Write,
+    F: FnMut(u32) -> Option<(u16, usize)>,
+{
+    /// Create a new logger writing to the given output and decoding with the given
+    /// named backend.
+    pub fn new(out: W, backend: &'static str, decoder: F) -> Self {
+        JsonLines { out, backend, decoder }
+    }
+
+    /// Decode the given word, log its diagnostics, and return the decoder result.
+    pub fn decode(&mut self, word: u32) -> io::Result<Option<(u16, usize)>> {
+        let diag = Diagnostic::capture(self.backend, &mut self.decoder, word);
+        diag.write_json(&mut self.out)?;
+
+        Ok(diag.result)
+    }
+
+    /// Consume the logger and return its output.
+    pub fn into_inner(self) -> W { self.out }
+}
+
+/// Write the given string as a quoted JSON string.
+fn write_string<W: Write>(w: &mut W, s: &str) -> io::Result<()> {
+    w.write_all(b"\"")?;
+
+    for c in s.chars() {
+        match c {
+            '"' => w.write_all(b"\\\"")?,
+            '\\' => w.write_all(b"\\\\")?,
+            c if (c as u32) < 0x20 => write!(w, "\\u{:04x}", c as u32)?,
+            c => write!(w, "{}", c)?,
+        }
+    }
+
+    w.write_all(b"\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn json(d: &Diagnostic) -> String {
+        let mut out = Vec::new();
+        d.write_json(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_json() {
+        let w = encode(0b000000011) ^ 0b10000000000000001;
+
+        let mut d = Diagnostic::capture("scalar", ::decode, w);
+        assert_eq!(d.result, Some((0b000000011, 2)));
+        assert_eq!(d.correction(), Some(0b10000000000000001));
+        assert_ne!(d.syndrome, 0);
+
+        d.nanos = 42;
+        assert_eq!(json(&d), format!(
+            "{{\"word\":{},\"syndrome\":{},\"data\":3,\"errors\":2,\
+             \"correction\":65537,\"backend\":\"scalar\",\"nanos\":42}}\n",
+            w, d.syndrome));
+
+        let mut d = Diagnostic::capture("a\"b\\\n", |_| None, 0b111);
+        d.nanos = 0;
+        assert_eq!(json(&d), format!(
+            "{{\"word\":7,\"syndrome\":{},\"data\":null,\"errors\":null,\
+             \"correction\":null,\"backend\":\"a\\\"b\\\\\\u000a\",\"nanos\":0}}\n",
+            d.syndrome));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let mut log = JsonLines::new(Vec::new(), "scalar", ::decode);
+
+        for d in 0..10 {
+            assert_eq!(log.decode(encode(d)).unwrap(), Some((d, 0)));
+        }
+
+        let out = String::from_utf8(log.into_inner()).unwrap();
+        assert_eq!(out.lines().count(), 10);
+        assert!(out.lines().all(|l| l.contains("\"syndrome\":0,") && l.ends_with('}')));
+    }
+}
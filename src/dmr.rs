@@ -0,0 +1,359 @@
+//! DMR signalling fields carried by the quadrature residue code.
+//!
+//! The EMB word in each voice burst carries a 2-bit link control start/stop (LCSS)
+//! value, which marks the burst's embedded signalling fragment as the first, a
+//! continuation, or the last of a multi-fragment message, or as a standalone fragment.
+//! Only a few orderings of these values are possible, so tracking them across a
+//! superframe catches many EMB words that were miscorrected into another valid codeword,
+//! before their fragments are assembled into a corrupt embedded LC.
+//!
+//! The TACT field in each CACH burst similarly carries an access type, the TDMA channel
+//! of the following burst, and an LCSS value for the short LC fragments. Once its
+//! Hamming FEC has been removed, the 4 information bits can be interpreted with
+//! [`Tact`](struct.Tact.html) and checked across bursts with
+//! [`TactTracker`](struct.TactTracker.html).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::dmr::{Lcss, LcssValidator, Progress};
+//!
+//! let mut v = LcssValidator::new();
+//!
+//! assert_eq!(v.next(Lcss::First), Ok(Progress::Partial(1)));
+//! assert_eq!(v.next(Lcss::Continuation), Ok(Progress::Partial(2)));
+//! assert_eq!(v.next(Lcss::Continuation), Ok(Progress::Partial(3)));
+//! assert_eq!(v.next(Lcss::Last), Ok(Progress::Complete));
+//!
+//! // A last fragment can't follow a completed message.
+//! assert!(v.next(Lcss::Last).is_err());
+//! ```
+
+/// Link control start/stop value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Lcss {
+    /// Standalone fragment, or no embedded signalling (`00`).
+    Single,
+    /// First fragment of a message (`01`).
+    First,
+    /// Last fragment of a message (`10`).
+    Last,
+    /// Continuation fragment of a message (`11`).
+    Continuation,
+}
+
+impl Lcss {
+    /// Parse the given 2 LCSS bits.
+    pub fn from_bits(bits: u8) -> Self {
+        assert_eq!(bits >> 2, 0);
+
+        match bits {
+            0b00 => Lcss::Single,
+            0b01 => Lcss::First,
+            0b10 => Lcss::Last,
+            _ => Lcss::Continuation,
+        }
+    }
+
+    /// Get the 2 LCSS bits.
+    pub fn bits(self) -> u8 {
+        match self {
+            Lcss::Single => 0b00,
+            Lcss::First => 0b01,
+            Lcss::Last => 0b10,
+            Lcss::Continuation => 0b11,
+        }
+    }
+}
+
+/// Number of fragments in an embedded LC message, one per voice burst B to E.
+const FRAGMENTS: usize = 4;
+
+/// State of the message after an accepted LCSS value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// A standalone fragment was received.
+    Single,
+    /// The given number of fragments of a message has been received so far.
+    Partial(usize),
+    /// The last fragment of a message was received.
+    Complete,
+}
+
+/// An LCSS value that can't occur at its position in the sequence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LcssError {
+    /// The rejected value.
+    pub lcss: Lcss,
+    /// Number of fragments of the current message received before the rejected value.
+    pub fragments: usize,
+}
+
+impl std::fmt::Display for LcssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "unexpected LCSS {:?} after {} fragments", self.lcss, self.fragments)
+    }
+}
+
+impl std::error::Error for LcssError {}
+
+/// Tracks the LCSS values of successive EMB words and rejects impossible sequences.
+///
+/// A message is a first fragment, two continuations, and a last fragment, and standalone
+/// fragments can only occur between messages. After a rejected value, the validator
+/// resynchronizes: a rejected first or standalone fragment starts over from that value,
+/// and any other rejected value discards the message in progress.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LcssValidator {
+    /// Number of fragments received of the message in progress.
+    fragments: usize,
+}
+
+impl LcssValidator {
+    /// Create a new validator, expecting the start of a message or a standalone
+    /// fragment.
+    pub fn new() -> Self { Self::default() }
+
+    /// Check the given LCSS value against the sequence so far.
+    pub fn next(&mut self, lcss: Lcss) -> Result<Progress, LcssError> {
+        let prev = self.fragments;
+
+        let valid = match lcss {
+            Lcss::Single | Lcss::First => prev == 0,
+            Lcss::Continuation => prev > 0 && prev < FRAGMENTS - 1,
+            Lcss::Last => prev == FRAGMENTS - 1,
+        };
+
+        self.fragments = match lcss {
+            Lcss::First => 1,
+            Lcss::Continuation if valid => prev + 1,
+            _ => 0,
+        };
+
+        if !valid {
+            return Err(LcssError { lcss, fragments: prev });
+        }
+
+        Ok(match lcss {
+            Lcss::Single => Progress::Single,
+            Lcss::Last => Progress::Complete,
+            _ => Progress::Partial(self.fragments),
+        })
+    }
+
+    /// Number of fragments received of the message in progress.
+    pub fn fragments(&self) -> usize { self.fragments }
+
+    /// Discard any message in progress, such as after a lost burst.
+    pub fn reset(&mut self) { *self = Self::default() }
+}
+
+/// Outbound channel access type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessType {
+    /// The inbound channel is idle.
+    Idle,
+    /// The inbound channel is busy.
+    Busy,
+}
+
+/// TDMA channel, or slot, of a burst.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TdmaChannel {
+    /// Channel 1.
+    First,
+    /// Channel 2.
+    Second,
+}
+
+impl TdmaChannel {
+    /// Get the other channel.
+    pub fn other(self) -> Self {
+        match self {
+            TdmaChannel::First => TdmaChannel::Second,
+            TdmaChannel::Second => TdmaChannel::First,
+        }
+    }
+}
+
+/// Information bits of a CACH TACT field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tact {
+    /// Access type of the inbound channel.
+    pub access: AccessType,
+    /// TDMA channel of the following burst.
+    pub channel: TdmaChannel,
+    /// Short LC fragment marker.
+    pub lcss: Lcss,
+}
+
+impl Tact {
+    /// Parse the given 4 TACT information bits, in the on-air order `AT TC LCSS`.
+    pub fn from_bits(bits: u8) -> Self {
+        assert_eq!(bits >> 4, 0);
+
+        Tact {
+            access: if bits >> 3 == 1 { AccessType::Busy } else { AccessType::Idle },
+            channel: if bits >> 2 & 1 == 1 { TdmaChannel::Second } else { TdmaChannel::First },
+            lcss: Lcss::from_bits(bits & 0b11),
+        }
+    }
+
+    /// Get the 4 TACT information bits.
+    pub fn bits(&self) -> u8 {
+        let at = match self.access {
+            AccessType::Idle => 0,
+            AccessType::Busy => 1,
+        };
+
+        let tc = match self.channel {
+            TdmaChannel::First => 0,
+            TdmaChannel::Second => 1,
+        };
+
+        at << 3 | tc << 2 | self.lcss.bits()
+    }
+}
+
+/// A TACT field that's inconsistent with the preceding CACH bursts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TactError {
+    /// The TDMA channel didn't alternate from the previous burst.
+    Channel(TdmaChannel),
+    /// The short LC fragment sequence is impossible.
+    Lcss(LcssError),
+}
+
+impl std::fmt::Display for TactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            TactError::Channel(tc) => write!(f, "TDMA channel {:?} repeated", tc),
+            TactError::Lcss(ref e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TactError {}
+
+/// Tracks the TACT fields of successive CACH bursts and rejects inconsistent ones.
+///
+/// The TDMA channel must alternate from burst to burst, and the LCSS values must form
+/// valid short LC sequences as checked by [`LcssValidator`](struct.LcssValidator.html).
+/// A channel error is reported in preference to an LCSS error, and either way the
+/// tracker resynchronizes to the rejected field.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TactTracker {
+    /// Channel of the previous burst, if any.
+    channel: Option<TdmaChannel>,
+    /// Short LC fragment sequence.
+    lcss: LcssValidator,
+}
+
+impl TactTracker {
+    /// Create a new tracker with no previous bursts.
+    pub fn new() -> Self { Self::default() }
+
+    /// Check the given TACT field against the previous bursts.
+    pub fn next(&mut self, tact: Tact) -> Result<Progress, TactError> {
+        let prev = self.channel.replace(tact.channel);
+        let lcss = self.lcss.next(tact.lcss);
+
+        match prev {
+            Some(tc) if tc == tact.channel => Err(TactError::Channel(tc)),
+            _ => lcss.map_err(TactError::Lcss),
+        }
+    }
+
+    /// Forget the previous bursts, such as after a lost burst.
+    pub fn reset(&mut self) { *self = Self::default() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lcss_bits() {
+        for b in 0..4 {
+            assert_eq!(Lcss::from_bits(b).bits(), b);
+        }
+    }
+
+    #[test]
+    fn test_validator() {
+        let mut v = LcssValidator::new();
+
+        assert_eq!(v.next(Lcss::Single), Ok(Progress::Single));
+        assert_eq!(v.next(Lcss::Single), Ok(Progress::Single));
+        assert_eq!(v.next(Lcss::First), Ok(Progress::Partial(1)));
+
+        // Interrupted message restarts on a new first fragment.
+        assert_eq!(v.next(Lcss::First), Err(LcssError { lcss: Lcss::First, fragments: 1 }));
+        assert_eq!(v.fragments(), 1);
+        assert_eq!(v.next(Lcss::Continuation), Ok(Progress::Partial(2)));
+
+        // Message ending early.
+        assert_eq!(v.next(Lcss::Last), Err(LcssError { lcss: Lcss::Last, fragments: 2 }));
+        assert_eq!(v.fragments(), 0);
+
+        // Continuation outside a message.
+        assert!(v.next(Lcss::Continuation).is_err());
+        assert_eq!(v.fragments(), 0);
+
+        v.next(Lcss::First).unwrap();
+        v.next(Lcss::Continuation).unwrap();
+        v.next(Lcss::Continuation).unwrap();
+
+        // Too many continuations.
+        assert_eq!(v.next(Lcss::Continuation),
+                   Err(LcssError { lcss: Lcss::Continuation, fragments: 3 }));
+
+        v.next(Lcss::First).unwrap();
+        v.next(Lcss::Continuation).unwrap();
+
+        // Standalone fragment inside a message.
+        assert!(v.next(Lcss::Single).is_err());
+        assert_eq!(v.fragments(), 0);
+        assert_eq!(v.next(Lcss::Single), Ok(Progress::Single));
+
+        v.next(Lcss::First).unwrap();
+        v.reset();
+        assert_eq!(v, LcssValidator::new());
+    }
+
+    #[test]
+    fn test_tact_bits() {
+        for b in 0..16 {
+            assert_eq!(Tact::from_bits(b).bits(), b);
+        }
+
+        assert_eq!(Tact::from_bits(0b1101), Tact {
+            access: AccessType::Busy,
+            channel: TdmaChannel::Second,
+            lcss: Lcss::First,
+        });
+    }
+
+    #[test]
+    fn test_tact_tracker() {
+        let mut t = TactTracker::new();
+
+        assert_eq!(t.next(Tact::from_bits(0b1000)), Ok(Progress::Single));
+        assert_eq!(t.next(Tact::from_bits(0b1101)), Ok(Progress::Partial(1)));
+        assert_eq!(t.next(Tact::from_bits(0b0011)), Ok(Progress::Partial(2)));
+
+        // Repeated channel.
+        assert_eq!(t.next(Tact::from_bits(0b0011)),
+                   Err(TactError::Channel(TdmaChannel::First)));
+
+        assert_eq!(t.next(Tact::from_bits(0b0110)), Ok(Progress::Complete));
+
+        // Impossible fragment on the right channel.
+        assert_eq!(t.next(Tact::from_bits(0b0010)),
+                   Err(TactError::Lcss(LcssError { lcss: Lcss::Last, fragments: 0 })));
+
+        t.reset();
+        assert_eq!(t.next(Tact::from_bits(0b0000)), Ok(Progress::Single));
+        assert_eq!(TdmaChannel::First.other(), TdmaChannel::Second);
+    }
+}
@@ -0,0 +1,137 @@
+//! C interface to the base code.
+//!
+//! These functions have stable C names and signatures suitable for
+//! [cbindgen](https://github.com/mozilla/cbindgen), so existing C and C++ DMR and P25
+//! stacks can link the crate as a static or shared library. They never panic: every
+//! failure is reported through the returned status code, which is one of the
+//! `CAI_CYCLIC_*` constants. The equivalent C declarations are
+//!
+//! ```c
+//! #define CAI_CYCLIC_OK 0
+//! #define CAI_CYCLIC_ERR_RANGE -1
+//! #define CAI_CYCLIC_ERR_UNCORRECTABLE -2
+//! #define CAI_CYCLIC_ERR_NULL -3
+//!
+//! int32_t cai_cyclic_encode(uint16_t data, uint32_t *word);
+//! int32_t cai_cyclic_decode(uint32_t word, uint16_t *data, uint32_t *errors);
+//! ```
+//!
+//! The crate is built as a plain Rust library by default, so the C library has to be
+//! requested explicitly, for example with
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type staticlib
+//! ```
+//!
+//! or `--crate-type cdylib` for a shared library.
+
+use {CodeError, try_decode, try_encode};
+
+/// The call succeeded.
+pub const CAI_CYCLIC_OK: i32 = 0;
+/// The input had bits set beyond the data or codeword length.
+pub const CAI_CYCLIC_ERR_RANGE: i32 = -1;
+/// The word had more errors than can be corrected.
+pub const CAI_CYCLIC_ERR_UNCORRECTABLE: i32 = -2;
+/// A required output pointer was null.
+pub const CAI_CYCLIC_ERR_NULL: i32 = -3;
+
+/// Encode the given 9 data bits into a 17-bit codeword, stored in `word` on success.
+///
+/// # Safety
+///
+/// `word` must be null or valid for writing a `uint32_t`.
+#[no_mangle]
+pub unsafe extern "C" fn cai_cyclic_encode(data: u16, word: *mut u32) -> i32 {
+    if word.is_null() {
+        return CAI_CYCLIC_ERR_NULL;
+    }
+
+    match try_encode(data) {
+        Ok(w) => {
+            *word = w;
+            CAI_CYCLIC_OK
+        },
+        Err(e) => status(e),
+    }
+}
+
+/// Decode the given 17-bit word, storing the 9 data bits in `data` and the number of
+/// corrected bits in `errors` on success. `errors` may be null if the count isn't
+/// needed.
+///
+/// # Safety
+///
+/// `data` must be null or valid for writing a `uint16_t`, and `errors` must be null or
+/// valid for writing a `uint32_t`.
+#[no_mangle]
+pub unsafe extern "C" fn cai_cyclic_decode(word: u32, data: *mut u16, errors: *mut u32)
+    -> i32
+{
+    if data.is_null() {
+        return CAI_CYCLIC_ERR_NULL;
+    }
+
+    match try_decode(word) {
+        Ok(d) => {
+            *data = d.data;
+
+            if !errors.is_null() {
+                *errors = d.errors as u32;
+            }
+
+            CAI_CYCLIC_OK
+        },
+        Err(e) => status(e),
+    }
+}
+
+/// Map the given error to its status code.
+fn status(e: CodeError) -> i32 {
+    match e {
+        CodeError::InputOutOfRange => CAI_CYCLIC_ERR_RANGE,
+        CodeError::Uncorrectable => CAI_CYCLIC_ERR_UNCORRECTABLE,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ptr;
+    use encode;
+
+    #[test]
+    fn test_encode() {
+        let mut word = 0;
+
+        unsafe {
+            assert_eq!(cai_cyclic_encode(0b101010101, &mut word), CAI_CYCLIC_OK);
+            assert_eq!(word, encode(0b101010101));
+
+            assert_eq!(cai_cyclic_encode(1 << 9, &mut word), CAI_CYCLIC_ERR_RANGE);
+            assert_eq!(cai_cyclic_encode(0, ptr::null_mut()), CAI_CYCLIC_ERR_NULL);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let (mut data, mut errors) = (0, 0);
+
+        unsafe {
+            assert_eq!(cai_cyclic_decode(encode(0x1a5) ^ 0b11, &mut data, &mut errors),
+                       CAI_CYCLIC_OK);
+            assert_eq!((data, errors), (0x1a5, 2));
+
+            assert_eq!(cai_cyclic_decode(encode(0x0f0), &mut data, ptr::null_mut()),
+                       CAI_CYCLIC_OK);
+            assert_eq!(data, 0x0f0);
+
+            assert_eq!(cai_cyclic_decode(0x1f, &mut data, &mut errors),
+                       CAI_CYCLIC_ERR_UNCORRECTABLE);
+            assert_eq!(cai_cyclic_decode(1 << 17, &mut data, &mut errors),
+                       CAI_CYCLIC_ERR_RANGE);
+            assert_eq!(cai_cyclic_decode(0, ptr::null_mut(), &mut errors),
+                       CAI_CYCLIC_ERR_NULL);
+        }
+    }
+}
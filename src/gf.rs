@@ -0,0 +1,117 @@
+//! Arithmetic over the small binary extension fields used by the algebraic codes.
+
+/// GF(2<sup>6</sup>) with primitive polynomial x<sup>6</sup> + x + 1.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+pub static GF64: Field = Field::new(6, 0b1000011);
+
+/// Finite field of characteristic 2, with log and antilog tables.
+pub struct Field {
+    /// Number of nonzero elements.
+    pub order: usize,
+    /// Powers of the primitive element α, repeated twice so products of two powers
+    /// don't need reducing.
+    pub exp: [u8; 512],
+    /// Logarithm to base α of each nonzero element.
+    pub log: [u8; 256],
+}
+
+impl Field {
+    /// Build the field with 2<sup>bits</sup> elements generated by the given primitive
+    /// polynomial, with the x<sup>bits</sup> term included.
+    pub const fn new(bits: u32, poly: u32) -> Self {
+        let order = (1 << bits) - 1;
+        let mut field = Field { order, exp: [0; 512], log: [0; 256] };
+        let mut x = 1;
+        let mut i = 0;
+
+        while i < order {
+            assert!(i == 0 || x != 1, "polynomial isn't primitive");
+
+            field.exp[i] = x as u8;
+            field.exp[i + order] = x as u8;
+            field.log[x as usize] = i as u8;
+
+            x <<= 1;
+
+            if x >> bits != 0 {
+                x ^= poly;
+            }
+
+            i += 1;
+        }
+
+        field
+    }
+
+    /// The primitive element raised to the given power.
+    pub fn alpha(&self, power: usize) -> u8 {
+        self.exp[power % self.order]
+    }
+
+    /// Multiply two elements.
+    pub const fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    /// Divide two elements, with a nonzero divisor.
+    pub fn div(&self, a: u8, b: u8) -> u8 {
+        assert!(b != 0);
+
+        if a == 0 {
+            0
+        } else {
+            let (la, lb) = (self.log[a as usize] as usize, self.log[b as usize] as usize);
+            self.exp[la + self.order - lb]
+        }
+    }
+
+    /// Evaluate the given polynomial, with coefficients from the lowest degree, at the
+    /// given point.
+    pub fn eval(&self, poly: &[u8], x: u8) -> u8 {
+        poly.iter().rev().fold(0, |acc, &c| self.mul(acc, x) ^ c)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_field() {
+        let gf256 = Field::new(8, 0b100011101);
+
+        assert_eq!(GF64.alpha(6), 0b000011);
+        assert_eq!(gf256.alpha(8), 0b00011101);
+
+        for gf in &[&GF64, &gf256] {
+            // The nonzero elements are a cyclic group under multiplication.
+            assert_eq!(gf.alpha(0), 1);
+            assert_eq!(gf.alpha(gf.order), 1);
+
+            for a in 1..gf.order + 1 {
+                for b in 1..gf.order + 1 {
+                    assert_eq!(gf.div(gf.mul(a as u8, b as u8), b as u8), a as u8);
+                }
+            }
+
+            let mut seen = [false; 256];
+
+            for i in 0..gf.order {
+                seen[gf.alpha(i) as usize] = true;
+            }
+
+            assert!(seen[1..gf.order + 1].iter().all(|&s| s));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "polynomial isn't primitive")]
+    fn test_not_primitive() {
+        // x^6 + x^3 + 1 has order 9.
+        Field::new(6, 0b1001001);
+    }
+}
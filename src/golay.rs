@@ -0,0 +1,299 @@
+//! Encoding and decoding of the (23, 12, 7) Golay code used alongside the base code in
+//! DMR and P25 headers.
+//!
+//! The generator polynomial for the code is given by
+//!
+//! > g(x) = x<sup>11</sup> + x<sup>10</sup> + x<sup>6</sup> + x<sup>5</sup> +
+//! > x<sup>4</sup> + x<sup>2</sup> + 1
+//!
+//! Each 23-bit codeword holds the 12 data bits in the MSBs, followed by the 11 parity
+//! bits. The code is perfect: every 11-bit syndrome corresponds to exactly one error
+//! pattern of weight 3 or less, so every word decodes, correcting up to 3 errors.
+//!
+//! ## Extended code
+//!
+//! The extended (24, 12, 8) code appends an even parity bit over the whole codeword in
+//! the LSB, so it still corrects up to 3 errors but also detects 4. This is handled by
+//! [`encode_extended`](fn.encode_extended.html) and
+//! [`decode_extended`](fn.decode_extended.html).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::golay;
+//!
+//! let w = golay::encode(0xabc);
+//! assert_eq!(golay::decode(w ^ 0b1000100000001), Some((0xabc, 3)));
+//!
+//! let x = golay::encode_extended(0xabc);
+//! assert_eq!(golay::decode_extended(x ^ 0b1000100000001), Some((0xabc, 3)));
+//! assert_eq!(golay::decode_extended(x ^ 0b1000100000011), None);
+//! ```
+
+use code::{BlockCode, Encoder, Decoder};
+use {load, TableError};
+
+/// Encode the given 12 data bits into a 23-bit codeword.
+pub fn encode(data: u16) -> u32 {
+    assert_eq!(data >> 12, 0);
+
+    let word = (data as u32) << 11;
+    word | remainder(word)
+}
+
+/// Try to decode the given 23-bit word to the nearest codeword, correcting up to 3
+/// errors.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 12 data
+/// bits and `err` is the number of corrected bits. Since the code is perfect, every
+/// word within range decodes, and a word with more than 3 errors is miscorrected.
+pub fn decode(word: u32) -> Option<(u16, usize)> {
+    assert_eq!(word >> 23, 0);
+
+    let pat = PATTERNS[remainder(word) as usize];
+    let fixed = word ^ pat;
+
+    Some(((fixed >> 11) as u16, pat.count_ones() as usize))
+}
+
+/// Encode the given 12 data bits into a 24-bit extended codeword, with the overall
+/// parity bit in the LSB.
+pub fn encode_extended(data: u16) -> u32 {
+    let word = encode(data);
+    word << 1 | word.count_ones() & 1
+}
+
+/// Try to decode the given 24-bit extended word, correcting up to 3 errors and
+/// detecting 4.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 12 data
+/// bits and `err` is the number of corrected bits, including the parity bit. Otherwise,
+/// return `None` to indicate an unrecoverable error.
+pub fn decode_extended(word: u32) -> Option<(u16, usize)> {
+    assert_eq!(word >> 24, 0);
+
+    let (data, _) = decode(word >> 1)?;
+
+    // With a minimum distance of 8, a word with 4 errors is at least 4 away from every
+    // extended codeword, including whichever one the base decoder chose.
+    match (encode_extended(data) ^ word).count_ones() as usize {
+        err if err <= 3 => Some((data, err)),
+        _ => None,
+    }
+}
+
+/// Verify the syndrome table against every correctable error pattern.
+///
+/// This is intended to be run once at startup to detect corrupted flash or a bad port.
+pub fn verify_tables() -> Result<(), TableError> {
+    if check_patterns(&PATTERNS) { Ok(()) } else { Err(TableError::Patterns) }
+}
+
+/// The (23, 12, 7) Golay code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Golay;
+
+impl Encoder for Golay {
+    type Data = u16;
+    type Codeword = u32;
+
+    fn encode(&self, data: u16) -> u32 { encode(data) }
+}
+
+impl Decoder for Golay {
+    fn decode(&self, word: u32) -> Option<(u16, usize)> { decode(word) }
+}
+
+impl BlockCode for Golay {
+    const N: usize = 23;
+    const K: usize = 12;
+    const D: usize = 7;
+}
+
+impl_dyn_block_code!(Golay, "golay23", u16, 12, u32, 23);
+
+/// The extended (24, 12, 8) Golay code, for use with the generic
+/// [`code`](../code/index.html) traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtendedGolay;
+
+impl Encoder for ExtendedGolay {
+    type Data = u16;
+    type Codeword = u32;
+
+    fn encode(&self, data: u16) -> u32 { encode_extended(data) }
+}
+
+impl Decoder for ExtendedGolay {
+    fn decode(&self, word: u32) -> Option<(u16, usize)> { decode_extended(word) }
+}
+
+impl BlockCode for ExtendedGolay {
+    const N: usize = 24;
+    const K: usize = 12;
+    const D: usize = 8;
+}
+
+impl_dyn_block_code!(ExtendedGolay, "golay24", u16, 12, u32, 24);
+
+/// Generator polynomial, with the x<sup>11</sup> term in the MSB.
+const GEN_POLY: u32 = 0b110001110101;
+
+/// Maps each 11-bit syndrome to an error pattern.
+///
+/// Every syndrome has a pattern, and only the zero syndrome has the zero pattern.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static PATTERNS: [u32; 2048] = build_patterns();
+
+/// Compute the remainder of the given 23-bit word divided by the generator polynomial.
+///
+/// This is both the parity for a shifted data word and the syndrome for a received word.
+const fn remainder(word: u32) -> u32 {
+    let mut rem = word;
+    let mut bit = 22;
+
+    while bit >= 11 {
+        if rem >> bit & 1 == 1 {
+            rem ^= GEN_POLY << (bit - 11);
+        }
+
+        bit -= 1;
+    }
+
+    rem
+}
+
+/// Build the syndrome table from every error pattern of weight 1 to 3.
+const fn build_patterns() -> [u32; 2048] {
+    let mut table = [0; 2048];
+    let mut i = 0;
+
+    while i < 23 {
+        table[remainder(1 << i) as usize] = 1 << i;
+
+        let mut j = i + 1;
+
+        while j < 23 {
+            table[remainder(1 << i | 1 << j) as usize] = 1 << i | 1 << j;
+
+            let mut k = j + 1;
+
+            while k < 23 {
+                let pat = 1 << i | 1 << j | 1 << k;
+                table[remainder(pat) as usize] = pat;
+                k += 1;
+            }
+
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+/// Check the given syndrome table against every error pattern of weight 1 to 3.
+fn check_patterns(patterns: &[u32]) -> bool {
+    // Repeated positions give the patterns of weight 1 and 2.
+    let mut pats = (0..23).flat_map(|i| {
+        (i..23).flat_map(move |j| (j..23).map(move |k| 1 << i | 1 << j | 1 << k))
+    });
+
+    patterns.len() == 2048 && load(&patterns[0]) == 0 &&
+        pats.all(|pat| load(&patterns[remainder(pat) as usize]) == pat)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(0), 0);
+        assert_eq!(encode(1), GEN_POLY);
+        assert_eq!(encode(0xfff), 0x7fffff);
+
+        // Every codeword is a multiple of the generator polynomial and at least 7 away
+        // from the zero codeword.
+        for d in 1..1 << 12 {
+            let w = encode(d);
+            assert_eq!(remainder(w), 0);
+            assert!(w.count_ones() >= 7);
+        }
+    }
+
+    #[test]
+    fn test_perfect() {
+        assert!(PATTERNS.iter().skip(1).all(|&p| p != 0 && p.count_ones() <= 3));
+        assert_eq!(PATTERNS[0], 0);
+    }
+
+    #[test]
+    fn test_decode() {
+        for d in (0..1 << 12).step_by(61) {
+            let w = encode(d);
+            assert_eq!(decode(w), Some((d, 0)));
+
+            for i in 0..23 {
+                assert_eq!(decode(w ^ 1 << i), Some((d, 1)));
+
+                for j in (i + 1)..23 {
+                    assert_eq!(decode(w ^ 1 << i ^ 1 << j), Some((d, 2)));
+
+                    for k in (j + 1)..23 {
+                        assert_eq!(decode(w ^ 1 << i ^ 1 << j ^ 1 << k), Some((d, 3)));
+                    }
+                }
+            }
+
+            // Four errors always land within 3 of another codeword.
+            assert_ne!(decode(w ^ 0b1111).map(|(d, _)| d), Some(d));
+        }
+    }
+
+    #[test]
+    fn test_extended() {
+        for d in 0..1 << 12 {
+            let x = encode_extended(d);
+            assert_eq!(x >> 1, encode(d));
+            assert_eq!(x.count_ones() & 1, 0);
+            assert!(d == 0 || x.count_ones() >= 8);
+        }
+
+        for d in (0..1 << 12).step_by(127) {
+            let x = encode_extended(d);
+            assert_eq!(decode_extended(x), Some((d, 0)));
+
+            for i in 0..24 {
+                for j in (i + 1)..24 {
+                    assert_eq!(decode_extended(x ^ 1 << i ^ 1 << j), Some((d, 2)));
+
+                    for k in (j + 1)..24 {
+                        let w = x ^ 1 << i ^ 1 << j ^ 1 << k;
+                        assert_eq!(decode_extended(w), Some((d, 3)));
+
+                        // Detect all four-bit errors.
+                        for l in (k + 1)..24 {
+                            assert_eq!(decode_extended(w ^ 1 << l), None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_tables() {
+        assert_eq!(verify_tables(), Ok(()));
+
+        let mut pats = PATTERNS;
+        pats[remainder(1 << 7) as usize] = 1 << 8;
+        assert!(!check_patterns(&pats));
+
+        let mut pats = PATTERNS;
+        pats[0] = 1;
+        assert!(!check_patterns(&pats));
+    }
+}
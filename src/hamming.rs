@@ -0,0 +1,344 @@
+//! Encoding and decoding of the Hamming codes used by DMR and P25.
+//!
+//! Each codeword holds the data bits in the MSBs, in transmission order, followed by the
+//! 4 parity bits. Every code here has a minimum distance of 3, so it corrects a single
+//! error. A double error is indistinguishable from a different single error and is
+//! miscorrected, so detecting it falls to the outer code, such as the columns of the
+//! product code or a CRC.
+//!
+//! The (15, 11, 3) code is the row code of DMR's BPTC (196, 96), and the (13, 9, 3)
+//! code, its column code, is the same code shortened by deleting 2 data MSBs. The
+//! shortened (10, 6, 3) code protects several fields of P25 voice frames.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::hamming;
+//!
+//! let w = hamming::encode_15_11(0b10110011101);
+//! assert_eq!(hamming::decode_15_11(w ^ 1 << 9), Some((0b10110011101, 1)));
+//!
+//! let w = hamming::encode_13_9(0b110010011);
+//! assert_eq!(hamming::decode_13_9(w ^ 1), Some((0b110010011, 1)));
+//!
+//! let w = hamming::encode_10_6(0b101110);
+//! assert_eq!(hamming::decode_10_6(w ^ 1 << 6), Some((0b101110, 1)));
+//! ```
+
+use code::{BlockCode, Encoder, Decoder};
+
+/// Encode the given 11 data bits into a 15-bit (15, 11, 3) codeword.
+pub fn encode_15_11(data: u16) -> u16 { H15_11.encode(data) }
+
+/// Try to decode the given 15-bit (15, 11, 3) word, correcting a single error.
+///
+/// Since the code is perfect, every word within range decodes, returning
+/// `Some((data, err))`, where `data` is the 11 data bits and `err` is the number of
+/// corrected bits.
+pub fn decode_15_11(word: u16) -> Option<(u16, usize)> { H15_11.decode(word) }
+
+/// Encode the given 9 data bits into a 13-bit (13, 9, 3) codeword.
+pub fn encode_13_9(data: u16) -> u16 { H13_9.encode(data) }
+
+/// Try to decode the given 13-bit (13, 9, 3) word, correcting a single error.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 9 data
+/// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
+/// an unrecoverable error.
+pub fn decode_13_9(word: u16) -> Option<(u16, usize)> { H13_9.decode(word) }
+
+/// Encode the given 6 data bits into a 10-bit P25 (10, 6, 3) codeword.
+pub fn encode_10_6(data: u8) -> u16 { H10_6.encode(data as u16) }
+
+/// Try to decode the given 10-bit P25 (10, 6, 3) word, correcting a single error.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 6 data
+/// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
+/// an unrecoverable error.
+pub fn decode_10_6(word: u16) -> Option<(u8, usize)> {
+    H10_6.decode(word).map(|(data, err)| (data as u8, err))
+}
+
+/// The (15, 11, 3) Hamming code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hamming15;
+
+impl Encoder for Hamming15 {
+    type Data = u16;
+    type Codeword = u16;
+
+    fn encode(&self, data: u16) -> u16 { encode_15_11(data) }
+}
+
+impl Decoder for Hamming15 {
+    fn decode(&self, word: u16) -> Option<(u16, usize)> { decode_15_11(word) }
+}
+
+impl BlockCode for Hamming15 {
+    const N: usize = 15;
+    const K: usize = 11;
+    const D: usize = 3;
+}
+
+impl_dyn_block_code!(Hamming15, "hamming15", u16, 11, u16, 15);
+
+/// The (13, 9, 3) Hamming code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hamming13;
+
+impl Encoder for Hamming13 {
+    type Data = u16;
+    type Codeword = u16;
+
+    fn encode(&self, data: u16) -> u16 { encode_13_9(data) }
+}
+
+impl Decoder for Hamming13 {
+    fn decode(&self, word: u16) -> Option<(u16, usize)> { decode_13_9(word) }
+}
+
+impl BlockCode for Hamming13 {
+    const N: usize = 13;
+    const K: usize = 9;
+    const D: usize = 3;
+}
+
+impl_dyn_block_code!(Hamming13, "hamming13", u16, 9, u16, 13);
+
+/// The P25 (10, 6, 3) Hamming code, for use with the generic
+/// [`code`](../code/index.html) traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hamming10;
+
+impl Encoder for Hamming10 {
+    type Data = u8;
+    type Codeword = u16;
+
+    fn encode(&self, data: u8) -> u16 { encode_10_6(data) }
+}
+
+impl Decoder for Hamming10 {
+    fn decode(&self, word: u16) -> Option<(u8, usize)> { decode_10_6(word) }
+}
+
+impl BlockCode for Hamming10 {
+    const N: usize = 10;
+    const K: usize = 6;
+    const D: usize = 3;
+}
+
+impl_dyn_block_code!(Hamming10, "hamming10", u8, 6, u16, 10);
+
+/// The (15, 11, 3) code.
+///
+/// Parity bit 0 is the XOR of data bits 0, 1, 2, 3, 5, 7, and 8 in transmission order,
+/// and so on for the other masks.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static H15_11: Hamming = Hamming::new(11, [
+    0b11110101100,
+    0b01111010110,
+    0b00111101011,
+    0b11101011001,
+]);
+
+/// The (13, 9, 3) code, with the (15, 11, 3) masks shortened by 2 bits.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static H13_9: Hamming = Hamming::new(9, [
+    0b110101100,
+    0b111010110,
+    0b111101011,
+    0b101011001,
+]);
+
+/// The P25 (10, 6, 3) code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static H10_6: Hamming = Hamming::new(6, [
+    0b111001,
+    0b110101,
+    0b101110,
+    0b011110,
+]);
+
+/// Systematic Hamming code with 4 parity bits.
+struct Hamming {
+    /// Number of data bits.
+    k: u32,
+    /// Data bits covered by each parity bit, with the first transmitted data bit in the
+    /// MSB and the first parity bit first.
+    checks: [u16; 4],
+    /// Maps each syndrome to a single-bit error pattern, or zero if it's invalid.
+    patterns: [u16; 16],
+}
+
+impl Hamming {
+    /// Create a new code with the given number of data bits and parity masks.
+    const fn new(k: u32, checks: [u16; 4]) -> Self {
+        let mut code = Hamming { k, checks, patterns: [0; 16] };
+        let mut i = 0;
+
+        while i < k + 4 {
+            let syndrome = code.syndrome(1 << i) as usize;
+
+            assert!(syndrome != 0 && code.patterns[syndrome] == 0,
+                    "columns of the parity-check matrix must be distinct and nonzero");
+
+            code.patterns[syndrome] = 1 << i;
+            i += 1;
+        }
+
+        code
+    }
+
+    /// Compute the 4 parity bits for the given data bits.
+    const fn parity(&self, data: u16) -> u16 {
+        let mut parity = 0;
+        let mut row = 0;
+
+        while row < 4 {
+            parity = parity << 1 | (self.checks[row] & data).count_ones() as u16 & 1;
+            row += 1;
+        }
+
+        parity
+    }
+
+    /// Compute the syndrome of the given word.
+    const fn syndrome(&self, word: u16) -> u16 {
+        self.parity(word >> 4) ^ word & 0b1111
+    }
+
+    /// Encode the given data bits into a codeword.
+    fn encode(&self, data: u16) -> u16 {
+        assert_eq!(data >> self.k, 0);
+        data << 4 | self.parity(data)
+    }
+
+    /// Try to decode the given word, correcting a single error.
+    fn decode(&self, word: u16) -> Option<(u16, usize)> {
+        assert_eq!(word >> (self.k + 4), 0);
+
+        match self.syndrome(word) {
+            0 => Some((word >> 4, 0)),
+            s => match self.patterns[s as usize] {
+                0 => None,
+                pat => Some(((word ^ pat) >> 4, 1)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Split the given word into bits in transmission order.
+    fn bits(word: u16, len: u32) -> Vec<bool> {
+        (0..len).rev().map(|i| word >> i & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_encode_15_11() {
+        // Parity equations for DMR, with bits in transmission order.
+        for data in 0..1 << 11 {
+            let d = bits(data, 11);
+            let c = bits(encode_15_11(data), 15)[11..].to_vec();
+
+            assert_eq!(c, vec![
+                d[0] ^ d[1] ^ d[2] ^ d[3] ^ d[5] ^ d[7] ^ d[8],
+                d[1] ^ d[2] ^ d[3] ^ d[4] ^ d[6] ^ d[8] ^ d[9],
+                d[2] ^ d[3] ^ d[4] ^ d[5] ^ d[7] ^ d[9] ^ d[10],
+                d[0] ^ d[1] ^ d[2] ^ d[4] ^ d[6] ^ d[7] ^ d[10],
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_encode_13_9() {
+        for data in 0..1 << 9 {
+            let d = bits(data, 9);
+            let c = bits(encode_13_9(data), 13)[9..].to_vec();
+
+            assert_eq!(c, vec![
+                d[0] ^ d[1] ^ d[3] ^ d[5] ^ d[6],
+                d[0] ^ d[1] ^ d[2] ^ d[4] ^ d[6] ^ d[7],
+                d[0] ^ d[1] ^ d[2] ^ d[3] ^ d[5] ^ d[7] ^ d[8],
+                d[0] ^ d[2] ^ d[4] ^ d[5] ^ d[8],
+            ]);
+
+            // Shortened from the (15, 11, 3) code.
+            assert_eq!(encode_13_9(data), encode_15_11(data));
+        }
+    }
+
+    #[test]
+    fn test_decode_13_9() {
+        // Two syndromes belong to the deleted positions.
+        assert_eq!(H13_9.patterns[1..].iter().filter(|&&p| p == 0).count(), 2);
+
+        for data in 0..1 << 9 {
+            let w = encode_13_9(data);
+            assert_eq!(decode_13_9(w), Some((data, 0)));
+
+            for i in 0..13 {
+                assert_eq!(decode_13_9(w ^ 1 << i), Some((data, 1)));
+            }
+        }
+
+        // Errors that look like a single error in a deleted position.
+        for i in 13..15 {
+            let s = H15_11.syndrome(1 << i);
+            assert_eq!(decode_13_9(s), None);
+        }
+    }
+
+    #[test]
+    fn test_encode_10_6() {
+        // Parity equations for P25, with bits in transmission order.
+        for data in 0..1 << 6 {
+            let d = bits(data as u16, 6);
+            let c = bits(encode_10_6(data), 10)[6..].to_vec();
+
+            assert_eq!(c, vec![
+                d[0] ^ d[1] ^ d[2] ^ d[5],
+                d[0] ^ d[1] ^ d[3] ^ d[5],
+                d[0] ^ d[2] ^ d[3] ^ d[4],
+                d[1] ^ d[2] ^ d[3] ^ d[4],
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_decode_10_6() {
+        let invalid = (1..16).filter(|&s| H10_6.patterns[s] == 0).collect::<Vec<_>>();
+        assert_eq!(invalid.len(), 5);
+
+        for data in 0..1 << 6 {
+            let w = encode_10_6(data);
+            assert_eq!(decode_10_6(w), Some((data, 0)));
+
+            for i in 0..10 {
+                assert_eq!(decode_10_6(w ^ 1 << i), Some((data, 1)));
+            }
+
+            for &s in &invalid {
+                assert_eq!(decode_10_6(w ^ s as u16), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_15_11() {
+        assert!(H15_11.patterns[1..].iter().all(|&p| p != 0));
+
+        for data in 0..1 << 11 {
+            let w = encode_15_11(data);
+            assert_eq!(decode_15_11(w), Some((data, 0)));
+
+            for i in 0..15 {
+                assert_eq!(decode_15_11(w ^ 1 << i), Some((data, 1)));
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Iterator adapters for encoding and decoding streams of words.
+//!
+//! The [`EncodeExt`](trait.EncodeExt.html) and [`DecodeExt`](trait.DecodeExt.html)
+//! extension traits add `map_encode()` and `map_decode()` to every iterator over data
+//! words and received words, so an iterator-based transmit or receive chain can pass
+//! words through the base code without wrapping the free functions in closures.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::iter::{EncodeExt, DecodeExt};
+//!
+//! let rx = [0b000000011, 0b101010101, 0b111111111].iter().cloned()
+//!     .map_encode()
+//!     .map(|w| w ^ 0b100)
+//!     .map_decode()
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(rx, vec![Some((0b000000011, 1)), Some((0b101010101, 1)),
+//!                     Some((0b111111111, 1))]);
+//! ```
+
+/// Iterator that encodes each 9-bit data word into a 17-bit codeword, as in
+/// [`encode`](../fn.encode.html).
+#[derive(Clone, Debug)]
+pub struct EncodeIter<I> {
+    /// Source of data words.
+    inner: I,
+}
+
+impl<I: Iterator<Item = u16>> Iterator for EncodeIter<I> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> { self.inner.next().map(::encode) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+impl<I: ExactSizeIterator<Item = u16>> ExactSizeIterator for EncodeIter<I> {}
+
+/// Iterator that decodes each received 17-bit word, with results as for
+/// [`decode`](../fn.decode.html).
+#[derive(Clone, Debug)]
+pub struct DecodeIter<I> {
+    /// Source of received words.
+    inner: I,
+}
+
+impl<I: Iterator<Item = u32>> Iterator for DecodeIter<I> {
+    type Item = Option<(u16, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> { self.inner.next().map(::decode) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.inner.size_hint() }
+}
+
+impl<I: ExactSizeIterator<Item = u32>> ExactSizeIterator for DecodeIter<I> {}
+
+/// Adds [`map_encode`](#method.map_encode) to iterators over data words.
+pub trait EncodeExt: Iterator<Item = u16> + Sized {
+    /// Encode each data word into a codeword.
+    fn map_encode(self) -> EncodeIter<Self> { EncodeIter { inner: self } }
+}
+
+impl<I: Iterator<Item = u16>> EncodeExt for I {}
+
+/// Adds [`map_decode`](#method.map_decode) to iterators over received words.
+pub trait DecodeExt: Iterator<Item = u32> + Sized {
+    /// Decode each received word.
+    fn map_decode(self) -> DecodeIter<Self> { DecodeIter { inner: self } }
+}
+
+impl<I: Iterator<Item = u32>> DecodeExt for I {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {decode, encode};
+
+    #[test]
+    fn test_encode() {
+        let words = (0..1 << 9).map_encode();
+        assert_eq!(words.len(), 512);
+        assert!(words.zip(0..).all(|(w, d)| w == encode(d)));
+    }
+
+    #[test]
+    fn test_decode() {
+        let words = (0..1 << 17).step_by(13);
+        let rx = words.clone().map_decode();
+
+        assert_eq!(rx.len(), words.len());
+        assert!(rx.zip(words).all(|(r, w)| r == decode(w)));
+        assert_eq!((0..0).map_decode().next(), None);
+    }
+}
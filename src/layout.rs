@@ -0,0 +1,133 @@
+//! Conversion between this crate's codeword layout and alternative layouts.
+//!
+//! This crate holds a codeword in the 17 LSBs of a `u32`, with the 9 data bits above the
+//! 8 parity bits and the first transmitted bit in the MSB. Other implementations hold
+//! the same code with the parity bits first or with the bits reflected, so their words
+//! can't be compared to this crate's directly. These routines convert between the
+//! layouts, and [`detect`](fn.detect.html) identifies the layout of another encoder by
+//! comparing every codeword bit for bit, to validate a migration.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::layout::{self, Layout};
+//!
+//! // A foreign encoder that emits the parity bits first.
+//! let foreign = |data: u16| {
+//!     let w = cai_cyclic::encode(data);
+//!     (w & 0xff) << 9 | w >> 8
+//! };
+//!
+//! assert_eq!(layout::detect(foreign), Some(Layout::ParityFirst));
+//!
+//! let w = foreign(0b110101001);
+//! assert_eq!(Layout::ParityFirst.to_canonical(w), cai_cyclic::encode(0b110101001));
+//! ```
+
+/// Arrangement of the data and parity bits within a 17-bit word.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// The layout used throughout this crate: data bits in the 9 MSBs, then the parity
+    /// bits, each field MSB first.
+    Canonical,
+    /// Parity bits in the 8 MSBs and data bits in the 9 LSBs, each field MSB first.
+    ParityFirst,
+    /// The whole canonical word bit-reversed, as for a word shifted out LSB first, so
+    /// the parity bits are in the MSBs and each field is reversed.
+    Reflected,
+    /// Each field reversed in place, with the data bits still in the 9 MSBs, as when the
+    /// data and parity are each packed LSB first.
+    ReflectedFields,
+}
+
+/// All supported layouts, starting with the canonical layout.
+pub const LAYOUTS: [Layout; 4] = [
+    Layout::Canonical,
+    Layout::ParityFirst,
+    Layout::Reflected,
+    Layout::ReflectedFields,
+];
+
+impl Layout {
+    /// Convert the given canonical 17-bit word into this layout.
+    pub fn from_canonical(self, word: u32) -> u32 {
+        assert_eq!(word >> 17, 0);
+
+        let (data, parity) = (word >> 8, word & 0xff);
+
+        match self {
+            Layout::Canonical => word,
+            Layout::ParityFirst => parity << 9 | data,
+            Layout::Reflected => reverse(word, 17),
+            Layout::ReflectedFields => reverse(data, 9) << 8 | reverse(parity, 8),
+        }
+    }
+
+    /// Convert the given 17-bit word in this layout into the canonical layout.
+    pub fn to_canonical(self, word: u32) -> u32 {
+        assert_eq!(word >> 17, 0);
+
+        match self {
+            Layout::ParityFirst => (word & 0x1ff) << 8 | word >> 9,
+            // The other conversions are their own inverses.
+            _ => self.from_canonical(word),
+        }
+    }
+}
+
+/// Find the layout of the given foreign encoder, which maps 9 data bits to a 17-bit
+/// codeword, by comparing all 512 of its codewords against this crate's.
+///
+/// Return `None` if the encoder doesn't match any supported layout, which suggests a
+/// different generator or a bit order not covered here.
+pub fn detect<F: FnMut(u16) -> u32>(mut encode: F) -> Option<Layout> {
+    let words = (0..1 << 9).map(&mut encode).collect::<Vec<_>>();
+
+    LAYOUTS.iter().cloned().find(|layout| {
+        ::codewords_with_data().all(|(data, w)| layout.from_canonical(w) == words[data as usize])
+    })
+}
+
+/// Reverse the order of the `n` LSBs of the given word.
+fn reverse(word: u32, n: u32) -> u32 {
+    word.reverse_bits() >> (32 - n)
+}
+
+#[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
+mod test {
+    use super::*;
+    use encode;
+
+    #[test]
+    fn test_conversions() {
+        let w = encode(0b100000011);
+        assert_eq!(w, 0b100000011_11010111);
+
+        assert_eq!(Layout::Canonical.from_canonical(w), w);
+        assert_eq!(Layout::ParityFirst.from_canonical(w), 0b11010111_100000011);
+        assert_eq!(Layout::Reflected.from_canonical(w), 0b11101011_110000001);
+        assert_eq!(Layout::ReflectedFields.from_canonical(w), 0b110000001_11101011);
+
+        for &layout in &LAYOUTS {
+            for w in ::codewords() {
+                let x = layout.from_canonical(w);
+                assert_eq!(x >> 17, 0);
+                assert_eq!(layout.to_canonical(x), w);
+            }
+
+            assert_eq!(layout.to_canonical(layout.from_canonical(0x1abcd)), 0x1abcd);
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        for &layout in &LAYOUTS {
+            assert_eq!(detect(|d| layout.from_canonical(encode(d))), Some(layout));
+        }
+
+        // A single wrong codeword fails the match.
+        assert_eq!(detect(|d| encode(d) ^ (d == 300) as u32), None);
+        assert_eq!(detect(|d| (d as u32) << 8), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Linear feedback shift register sequence generators.
+//!
+//! A register is described by its feedback polynomial, given with bit `i` holding the
+//! coefficient of x<sup>i</sup>, including the x<sup>n</sup> and constant terms, so
+//! x<sup>9</sup> + x<sup>5</sup> + 1 is `0b1000100001`. Both the Fibonacci (external
+//! XOR) and Galois (internal XOR) forms are provided. For the same polynomial they
+//! produce the same sequence up to a shift, which depends on how the seed loads the
+//! register, so the form should match whatever a specification or hardware uses.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::lfsr::Lfsr;
+//!
+//! // The PN9 sequence used for bit error rate test patterns.
+//! let mut pn9 = Lfsr::fibonacci(0b1000100001, 0x1ff);
+//! let first = pn9.next_bits(9);
+//!
+//! // A primitive polynomial of degree 9 gives a period of 511.
+//! for _ in 0..511 - 9 {
+//!     pn9.next_bit();
+//! }
+//!
+//! assert_eq!(pn9.next_bits(9), first);
+//! ```
+
+/// Register structure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Form {
+    /// Feedback is the XOR of tapped stages, shifted into one end of the register.
+    Fibonacci,
+    /// The output bit is XORed into the tapped stages as the register shifts.
+    Galois,
+}
+
+/// Linear feedback shift register with up to 63 stages.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lfsr {
+    /// Register structure.
+    form: Form,
+    /// Number of stages.
+    degree: u32,
+    /// Tapped stages, as a mask over the register state.
+    taps: u64,
+    /// Current register contents, with the next output bit in the MSB stage.
+    state: u64,
+}
+
+impl Lfsr {
+    /// Create a new register with the given structure, feedback polynomial, and initial
+    /// state.
+    ///
+    /// The next output bit is the MSB of the `degree`-bit seed. An all-zero seed gives
+    /// an all-zero sequence.
+    pub fn new(form: Form, poly: u64, seed: u64) -> Self {
+        assert!(poly & 1 == 1, "polynomial must have a constant term");
+
+        let degree = 63 - poly.leading_zeros();
+        assert!((1..=63).contains(&degree));
+        assert_eq!(seed >> degree, 0);
+
+        let taps = match form {
+            // Stage n - 1 - i holds the bit i steps ahead of the next output, so the
+            // recurrence coefficients map onto the register reversed.
+            Form::Fibonacci => (0..degree)
+                .filter(|&i| poly >> i & 1 == 1)
+                .fold(0, |taps, i| taps | 1 << (degree - 1 - i)),
+            Form::Galois => poly & mask(degree),
+        };
+
+        Lfsr { form, degree, taps, state: seed }
+    }
+
+    /// Create a new Fibonacci register, as in `new()`.
+    pub fn fibonacci(poly: u64, seed: u64) -> Self { Self::new(Form::Fibonacci, poly, seed) }
+
+    /// Create a new Galois register, as in `new()`.
+    pub fn galois(poly: u64, seed: u64) -> Self { Self::new(Form::Galois, poly, seed) }
+
+    /// Register structure.
+    pub fn form(&self) -> Form { self.form }
+
+    /// Number of register stages.
+    pub fn degree(&self) -> u32 { self.degree }
+
+    /// Current register contents, with the next output bit in the MSB.
+    pub fn state(&self) -> u64 { self.state }
+
+    /// Clock the register once and return the output bit.
+    pub fn next_bit(&mut self) -> bool {
+        let out = self.state >> (self.degree - 1) & 1 == 1;
+
+        self.state = match self.form {
+            Form::Fibonacci => {
+                let fb = (self.state & self.taps).count_ones() as u64 & 1;
+                (self.state << 1 | fb) & mask(self.degree)
+            },
+            Form::Galois => {
+                let shifted = self.state << 1 & mask(self.degree);
+                if out { shifted ^ self.taps } else { shifted }
+            },
+        };
+
+        out
+    }
+
+    /// Clock the register the given number of times, up to 64, and return the output
+    /// bits with the first in the MSB.
+    pub fn next_bits(&mut self, bits: u32) -> u64 {
+        assert!(bits <= 64);
+        (0..bits).fold(0, |word, _| word << 1 | self.next_bit() as u64)
+    }
+
+    /// XOR the generated sequence into the given bytes, MSB first, to scramble or
+    /// descramble them.
+    pub fn apply(&mut self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            *byte ^= self.next_bits(8) as u8;
+        }
+    }
+}
+
+impl Iterator for Lfsr {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> { Some(self.next_bit()) }
+}
+
+/// Mask covering the given number of low bits.
+fn mask(bits: u32) -> u64 {
+    (1 << bits) - 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Find the period of the given register's state sequence.
+    fn period(mut lfsr: Lfsr) -> usize {
+        let start = lfsr.state();
+        lfsr.next_bit();
+
+        (1..).find(|_| {
+            let done = lfsr.state() == start;
+            lfsr.next_bit();
+            done
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_period() {
+        assert_eq!(period(Lfsr::fibonacci(0b10011, 1)), 15);
+        assert_eq!(period(Lfsr::galois(0b10011, 1)), 15);
+        assert_eq!(period(Lfsr::fibonacci(0b1000100001, 0x1ff)), 511);
+        assert_eq!(period(Lfsr::galois(0b1000100001, 0x1ff)), 511);
+
+        // Not primitive: x^4 + x^3 + x^2 + x + 1 divides x^5 + 1.
+        assert_eq!(period(Lfsr::fibonacci(0b11111, 1)), 5);
+    }
+
+    #[test]
+    fn test_recurrence() {
+        // Both forms satisfy a[k + 9] = a[k + 5] + a[k].
+        for &form in &[Form::Fibonacci, Form::Galois] {
+            let bits = Lfsr::new(form, 0b1000100001, 0b101100111)
+                .take(600)
+                .collect::<Vec<_>>();
+
+            assert!((0..600 - 9).all(|k| bits[k + 9] == bits[k + 5] ^ bits[k]));
+        }
+    }
+
+    #[test]
+    fn test_same_sequence() {
+        let fib = Lfsr::fibonacci(0b10011, 0b1000).take(30).collect::<Vec<_>>();
+        let gal = Lfsr::galois(0b10011, 0b1000).take(30).collect::<Vec<_>>();
+
+        assert!((0..15).any(|s| fib[s..s + 15] == gal[..15]));
+        assert_eq!(Lfsr::fibonacci(0b10011, 0).next_bits(20), 0);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut buf = *b"scrambled";
+
+        Lfsr::galois(0b1000100001, 0x1ff).apply(&mut buf);
+        assert_ne!(&buf, b"scrambled");
+
+        Lfsr::galois(0b1000100001, 0x1ff).apply(&mut buf);
+        assert_eq!(&buf, b"scrambled");
+    }
+}
@@ -8,15 +8,77 @@
 //!
 //! It can detect up to 4 errors or correct up to 2 errors.
 //!
+//! The all-ones word is a codeword, so inverting every bit of a codeword gives another
+//! codeword, with the complemented data bits. An inverted received word therefore
+//! decodes with exactly the same error count as the original, and the code alone can't
+//! detect polarity inversion: that has to come from a sync pattern or higher-layer
+//! checks.
+//!
 //! ## DMR "quadrature residue" code
 //!
 //! The DMR air interface extends this code to (18, 9, 6) with an extra parity check bit
 //! in the LSB, then shortens it to (16, 7, 6) by deleting two MSB data bits. The extra
-//! parity bit is computed over the data bits using the mask `1010111`.
+//! parity bit is computed over the data bits using the mask `1010111`. This is handled
+//! by [`encode_dmr`](fn.encode_dmr.html) and [`decode_dmr`](fn.decode_dmr.html), and
+//! the unshortened extended code by [`encode_extended`](fn.encode_extended.html) and
+//! [`decode_extended`](fn.decode_extended.html).
 //!
 //! ## P25 "shortened cyclic" code
 //!
 //! The P25 air interface shortens this code to (16, 8, 5) by deleting the MSB data bit.
+//! This is handled by [`encode_p25`](fn.encode_p25.html) and
+//! [`decode_p25`](fn.decode_p25.html).
+//!
+//! Both variants, and other shortenings used by niche protocols, can also be described
+//! declaratively with the [`shortened`](shortened/index.html) module.
+//!
+//! ## Cargo features
+//!
+//! The base (17, 9, 5) code and its supporting modules are always available. Each other
+//! code family is gated behind its own feature, all enabled by default, so firmware can
+//! use `default-features = false` to leave out what it doesn't need:
+//!
+//! - `bch`: the [`bch`](bch/index.html) module.
+//! - `convolutional`: the [`convolutional`](convolutional/index.html) module.
+//! - `golay`: the [`golay`](golay/index.html) module.
+//! - `hamming`: the [`hamming`](hamming/index.html) module, and the
+//!   [`bptc`](bptc/index.html) product code built on it.
+//! - `pocsag`: the [`pocsag`](pocsag/index.html) module.
+//! - `reed-solomon`: the [`reed_solomon`](reed_solomon/index.html) module, and with
+//!   `golay`, the [`tdulc`](tdulc/index.html) link control chain built on both.
+//! - `trellis`: the [`trellis`](trellis/index.html) module.
+//!
+//! The remaining features are optional:
+//!
+//! - `big-tables`: enable the [`table`](table/index.html) module, with a complete
+//!   131072-entry decoding table for maximum throughput, and route
+//!   [`decode`](fn.decode.html) through a shared instance of it.
+//! - `cross-check`: run the scalar decoder alongside the accelerated decoders, the
+//!   `big-tables` lookup table and the `portable_simd` batch decoder, and panic with the
+//!   full context on any divergence. This catches corrupted tables in the field at the
+//!   cost of the speedup.
+//! - `diagnostics`: enable the [`diag`](diag/index.html) module, for exporting
+//!   per-word decode diagnostics as JSON lines.
+//! - `encode-table`: encode with a 512-entry table of codewords, built at compile time,
+//!   instead of multiplying by the generator matrix, for hot encoding paths such as
+//!   generating repeater bursts.
+//! - `ffi`: enable the [`ffi`](ffi/index.html) module, exporting C functions for linking
+//!   the crate's static or shared library into C and C++ stacks.
+//! - `link-section`: place the constant tables in the linker section named by the
+//!   `CAI_CYCLIC_LINK_SECTION` environment variable at build time, for example
+//!   `CAI_CYCLIC_LINK_SECTION=.itcm_rodata`, so a linker script can locate them in
+//!   flash-adjacent memory or ITCM. If the variable isn't set, the build script warns
+//!   and the tables stay in their default section.
+//! - `portable_simd`: enable the [`simd`](simd/index.html) module, with a batch decoder
+//!   that decodes 16 words in parallel, and a dispatcher that picks its AVX-512 or AVX2
+//!   build at runtime. This requires a nightly compiler, and the feature is ignored
+//!   with a build warning on stable.
+//! - `rayon`: enable [`par_encode_slice`](fn.par_encode_slice.html) and
+//!   [`par_decode_slice`](fn.par_decode_slice.html), which spread large batches, such as
+//!   reprocessed capture logs, over all cores with the `rayon` crate.
+//! - `sim`: enable the Monte Carlo harness and the binary symmetric and AWGN channel
+//!   models in the [`sim`](sim/index.html) module, for measuring bit and frame error
+//!   rates before and after decoding.
 //!
 //! ## References
 //!
@@ -24,14 +86,265 @@
 //! *Error Control Coding* (1983) and Roman's *Coding and Information Theory* (1992),
 //! p345.
 
+#![cfg_attr(cai_cyclic_simd, feature(portable_simd))]
+
 extern crate binfield_matrix;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
-use binfield_matrix::{matrix_mul, matrix_mul_systematic};
+use std::convert::TryFrom;
+
+use binfield_matrix::matrix_mul;
+
+#[macro_use]
+pub mod code;
+
+pub mod analysis;
+#[cfg(feature = "bch")]
+pub mod bch;
+#[cfg(feature = "hamming")]
+pub mod bptc;
+pub mod builder;
+#[cfg(feature = "convolutional")]
+pub mod convolutional;
+pub mod crc;
+#[cfg(feature = "diagnostics")]
+pub mod diag;
+pub mod dmr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(any(feature = "bch", feature = "reed-solomon"))]
+mod gf;
+#[cfg(feature = "golay")]
+pub mod golay;
+#[cfg(feature = "hamming")]
+pub mod hamming;
+pub mod iter;
+pub mod layout;
+pub mod lfsr;
+pub mod packed;
+#[cfg(feature = "pocsag")]
+pub mod pocsag;
+pub mod product;
+pub mod quality;
+#[cfg(feature = "reed-solomon")]
+pub mod reed_solomon;
+pub mod reference;
+pub mod repeat;
+pub mod shortened;
+pub mod sim;
+#[cfg(cai_cyclic_simd)]
+pub mod simd;
+pub mod soft;
+pub mod stats;
+#[cfg(feature = "big-tables")]
+pub mod table;
+#[cfg(all(feature = "golay", feature = "reed-solomon"))]
+pub mod tdulc;
+#[cfg(feature = "trellis")]
+pub mod trellis;
+pub mod vectors;
+pub mod vote;
+
+use code::{BlockCode, Encoder, Decoder};
 
 /// Encode the given 9 data bits into a 17-bit codeword.
-pub fn encode(data: u16) -> u32 {
+///
+/// This is a `const fn`, so codewords for fixed fields can be computed at compile time:
+///
+/// ```rust
+/// const IDLE: u32 = cai_cyclic::encode(0b101010101);
+/// assert_eq!(cai_cyclic::decode(IDLE), Some((0b101010101, 0)));
+/// ```
+pub const fn encode(data: u16) -> u32 {
+    assert!(data >> 9 == 0);
+
+    #[cfg(feature = "encode-table")]
+    return CODEWORDS[data as usize];
+
+    #[cfg(not(feature = "encode-table"))]
+    return matrix_encode(data);
+}
+
+/// Update the given 17-bit codeword for a change in a single data bit, counted from the
+/// data LSB.
+///
+/// The code is linear, so the codeword of `data ^ 1 << bit` is the codeword of `data`
+/// XORed with the codeword of `1 << bit`, the generator column for that bit. This
+/// applies that column directly rather than re-running the encoder, and as long as the
+/// given word is a codeword, the result is exactly `encode(data ^ 1 << bit)`. Errors in
+/// the given word carry over unchanged into the result.
+///
+/// ```rust
+/// let w = cai_cyclic::encode(0b000110001);
+/// assert_eq!(cai_cyclic::reencode_flip(w, 2), cai_cyclic::encode(0b000110101));
+/// ```
+pub const fn reencode_flip(codeword: u32, bit: usize) -> u32 {
+    assert!(bit < 9);
+    codeword ^ GEN_COLUMNS[bit]
+}
+
+/// Encode the given 9 data bits into a 17-bit codeword, as in
+/// [`encode`](fn.encode.html), without panicking on out-of-range input.
+pub fn try_encode(data: u16) -> Result<u32, CodeError> {
+    if data >> 9 == 0 {
+        Ok(encode(data))
+    } else {
+        Err(CodeError::InputOutOfRange)
+    }
+}
+
+/// Try to decode the given 17-bit word, as in [`decode`](fn.decode.html), without
+/// panicking on out-of-range input.
+pub fn try_decode(word: u32) -> Result<Decoded, CodeError> {
+    if word >> 17 != 0 {
+        return Err(CodeError::InputOutOfRange);
+    }
+
+    decode(word)
+        .map(|(data, errors)| Decoded { data, errors })
+        .ok_or(CodeError::Uncorrectable)
+}
+
+/// Encode the 9 LSBs of the given value into a 17-bit codeword, as in
+/// [`encode`](fn.encode.html), ignoring any higher bits.
+///
+/// This suits data taken straight out of a packed bit stream, where the bits above the
+/// field are unrelated.
+pub const fn encode_masked(data: u16) -> u32 {
+    encode(data & 0x1ff)
+}
+
+/// Try to decode the 17 LSBs of the given value, as in [`decode`](fn.decode.html),
+/// ignoring any higher bits.
+///
+/// This is unrelated to [`decode_mask`](fn.decode_mask.html), which returns the
+/// corrected bit positions.
+pub fn decode_masked(word: u32) -> Option<(u16, usize)> {
+    decode(word & 0x1ffff)
+}
+
+/// A successfully decoded word.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decoded {
+    /// The 9 data bits.
+    pub data: u16,
+    /// Number of corrected bits.
+    pub errors: usize,
+}
+
+/// Error from the fallible encoding and decoding functions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CodeError {
+    /// The input had bits set beyond the data or codeword length.
+    InputOutOfRange,
+    /// The word had more errors than can be corrected.
+    Uncorrectable,
+}
+
+impl std::fmt::Display for CodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match *self {
+            CodeError::InputOutOfRange => "input out of range",
+            CodeError::Uncorrectable => "uncorrectable word",
+        })
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+/// Nine data bits of the base code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Data9(u16);
+
+impl Data9 {
+    /// Wrap the given data bits, or return `None` if more than 9 bits are set.
+    pub fn new(data: u16) -> Option<Self> {
+        if data >> 9 == 0 { Some(Data9(data)) } else { None }
+    }
+
+    /// Raw data bits.
+    pub fn get(self) -> u16 { self.0 }
+
+    /// Encode the data bits into a codeword, as in [`encode`](fn.encode.html).
+    pub fn encode(self) -> Codeword17 { Codeword17(encode(self.0)) }
+}
+
+impl From<Data9> for u16 {
+    fn from(data: Data9) -> u16 { data.0 }
+}
+
+impl TryFrom<u16> for Data9 {
+    type Error = CodeError;
+
+    fn try_from(data: u16) -> Result<Self, CodeError> {
+        Data9::new(data).ok_or(CodeError::InputOutOfRange)
+    }
+}
+
+/// A 17-bit word of the base code, either a codeword or a received word that may
+/// contain errors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Codeword17(u32);
+
+impl Codeword17 {
+    /// Wrap the given word, or return `None` if more than 17 bits are set.
+    pub fn new(word: u32) -> Option<Self> {
+        if word >> 17 == 0 { Some(Codeword17(word)) } else { None }
+    }
+
+    /// Raw word bits.
+    pub fn get(self) -> u32 { self.0 }
+
+    /// The 9 data bits in the MSBs of the word, as received without correction.
+    pub fn data(self) -> Data9 { Data9((self.0 >> 8) as u16) }
+
+    /// The 8 parity bits in the LSBs of the word.
+    pub fn parity(self) -> u8 { self.0 as u8 }
+
+    /// Try to decode the word, with results as for [`decode`](fn.decode.html).
+    pub fn decode(self) -> Option<(Data9, usize)> {
+        decode(self.0).map(|(data, err)| (Data9(data), err))
+    }
+}
+
+impl From<Codeword17> for u32 {
+    fn from(word: Codeword17) -> u32 { word.0 }
+}
+
+impl TryFrom<u32> for Codeword17 {
+    type Error = CodeError;
+
+    fn try_from(word: u32) -> Result<Self, CodeError> {
+        Codeword17::new(word).ok_or(CodeError::InputOutOfRange)
+    }
+}
+
+/// Encode the given 9 data bits with the classic divide-by-g(x) shift register circuit,
+/// returning the register contents after each input bit.
+///
+/// Data bits are shifted in MSB first, and the register's x<sup>7</sup> stage is in the
+/// MSB of each state. The final state is the 8 parity bits of the codeword, which makes
+/// this useful for teaching and for verifying hardware LFSR encoders step by step.
+pub fn encode_traced(data: u16) -> [u8; 9] {
     assert_eq!(data >> 9, 0);
-    matrix_mul_systematic(data, GEN)
+
+    let mut states = [0; 9];
+    let mut reg = 0u8;
+
+    for (i, state) in states.iter_mut().enumerate() {
+        let feedback = (data >> (8 - i)) as u8 & 1 ^ reg >> 7;
+
+        reg <<= 1;
+
+        if feedback == 1 {
+            reg ^= GEN_POLY as u8;
+        }
+
+        *state = reg;
+    }
+
+    states
 }
 
 /// Try to decode the given 17-bit word to the nearest codeword, correcting up to 2
@@ -40,29 +353,507 @@ pub fn encode(data: u16) -> u32 {
 /// If decoding was successful, return `Some((data, err))`, where `data` is the 9 data
 /// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
 /// an unrecoverable error.
+///
+/// With the `big-tables` feature, a word with errors is instead looked up in the shared
+/// [`DecodeTable`](table/struct.DecodeTable.html), which is built on first use, while a
+/// clean codeword is still recognized by its syndrome without touching the table.
 pub fn decode(word: u32) -> Option<(u16, usize)> {
+    #[cfg(feature = "big-tables")]
+    return match syndrome(word) {
+        0 => Some(((word >> 8) as u16, 0)),
+        _ => table::shared().decode(word),
+    };
+
+    #[cfg(not(feature = "big-tables"))]
+    return decode_diagnosed(word).ok();
+}
+
+/// Encode each of the given data words into the corresponding output slot, as in
+/// [`encode`](fn.encode.html).
+///
+/// Both slices must have the same length.
+pub fn encode_slice(data: &[u16], out: &mut [u32]) {
+    assert_eq!(data.len(), out.len());
+
+    for (&d, w) in data.iter().zip(out.iter_mut()) {
+        *w = encode(d);
+    }
+}
+
+/// Decode each of the given 17-bit words into the corresponding output slot, as in
+/// [`decode`](fn.decode.html).
+///
+/// Both slices must have the same length.
+pub fn decode_slice(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    assert_eq!(words.len(), out.len());
+
+    for (&w, r) in words.iter().zip(out.iter_mut()) {
+        *r = decode(w);
+    }
+}
+
+/// Number of words handled by each task of the parallel slice functions, large enough
+/// to amortize the scheduling overhead.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK: usize = 4096;
+
+/// Encode each of the given data words into the corresponding output slot as in
+/// [`encode_slice`](fn.encode_slice.html), spreading the work over the rayon thread
+/// pool.
+///
+/// Both slices must have the same length.
+#[cfg(feature = "rayon")]
+pub fn par_encode_slice(data: &[u16], out: &mut [u32]) {
+    use rayon::prelude::*;
+
+    assert_eq!(data.len(), out.len());
+
+    data.par_chunks(PAR_CHUNK).zip(out.par_chunks_mut(PAR_CHUNK))
+        .for_each(|(data, out)| encode_slice(data, out));
+}
+
+/// Decode each of the given 17-bit words into the corresponding output slot as in
+/// [`decode_slice`](fn.decode_slice.html), spreading the work over the rayon thread
+/// pool.
+///
+/// Both slices must have the same length.
+#[cfg(feature = "rayon")]
+pub fn par_decode_slice(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    use rayon::prelude::*;
+
+    assert_eq!(words.len(), out.len());
+
+    words.par_chunks(PAR_CHUNK).zip(out.par_chunks_mut(PAR_CHUNK))
+        .for_each(|(words, out)| decode_slice(words, out));
+}
+
+/// Try to decode the given 17-bit word as in [`decode`](fn.decode.html), returning the
+/// positions of the corrected bits.
+///
+/// If decoding was successful, return `Some((data, mask))`, where `data` is the 9 data
+/// bits and `mask` has a set bit for each flipped position of the received word, so the
+/// corrected codeword is `word ^ mask`. Otherwise, return `None`.
+pub fn decode_mask(word: u32) -> Option<(u16, u32)> {
+    decode(word).map(|(data, _)| (data, encode(data) ^ word))
+}
+
+/// Try to decode the given 17-bit word as in [`decode`](fn.decode.html), describing the
+/// received word if decoding fails.
+///
+/// If decoding was successful, return `Ok((data, err))`. Otherwise, return the
+/// [`DecodeFailure`](struct.DecodeFailure.html), which upper layers can use to choose
+/// between requesting retransmission, flagging an erasure, or trying a stronger
+/// decoder.
+pub fn decode_diagnosed(word: u32) -> Result<(u16, usize), DecodeFailure> {
+    assert_eq!(word >> 17, 0);
+
+    let syndrome = syndrome(word);
+
+    if syndrome == 0 {
+        return Ok(((word >> 8) as u16, 0));
+    }
+
+    match PATTERNS[syndrome as usize] {
+        0 => Err(DecodeFailure {
+            syndrome,
+            // Rotating the word rotates its error pattern, so work out how close the
+            // nearest rotation comes to an error in the parity bits only.
+            min_weight: (0..17).scan(word, |word, _| {
+                let weight = self::syndrome(*word).count_ones();
+                *word = rotate_17(*word);
+                Some(weight)
+            }).min().unwrap(),
+        }),
+        pat => Ok((((word ^ pat) >> 8) as u16, pat.count_ones() as usize)),
+    }
+}
+
+/// Description of a word that couldn't be decoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DecodeFailure {
+    /// Syndrome of the received word.
+    pub syndrome: u8,
+    /// Smallest syndrome weight seen across all rotations of the word.
+    ///
+    /// This is always at least 3, since a weight of 2 or less would have been corrected
+    /// as errors in the parity bits. A weight of 3 means some rotation is explained by 3
+    /// errors in the parity bits, so the word may be just beyond the correction radius,
+    /// while a higher weight suggests a burst or a word that was never a codeword.
+    pub min_weight: u32,
+}
+
+/// Compute the 8-bit syndrome of the given 17-bit word.
+///
+/// The syndrome is zero exactly when the word is a codeword, and otherwise depends only
+/// on the error pattern. This is a single matrix multiply, much cheaper than a full
+/// decode.
+pub fn syndrome(word: u32) -> u8 {
+    assert_eq!(word >> 17, 0);
+    matrix_mul(word, &PAR)
+}
+
+/// Check if the given 17-bit word is a codeword.
+pub fn is_codeword(word: u32) -> bool {
+    syndrome(word) == 0
+}
+
+/// Count the set bits of the given 17-bit word.
+pub fn weight(word: u32) -> u32 {
+    assert_eq!(word >> 17, 0);
+    word.count_ones()
+}
+
+/// Count the positions where the given 17-bit words differ.
+pub fn hamming_distance(a: u32, b: u32) -> u32 {
+    weight(a ^ b)
+}
+
+/// Count the positions where the given 17-bit word differs from the codeword for the
+/// given 9 data bits.
+///
+/// This gives the number of errors a received word would have if `data` was sent, such
+/// as to compare a decode against a known transmission.
+pub fn distance_to_codeword(word: u32, data: u16) -> u32 {
+    hamming_distance(word, encode(data))
+}
+
+/// Classify the given 17-bit word without correcting it.
+///
+/// High-assurance users can reject any word that isn't `Clean`, rather than trusting
+/// corrections.
+pub fn detect(word: u32) -> Detection {
+    if is_codeword(word) {
+        return Detection::Clean;
+    }
+
+    match decode(word) {
+        Some((_, err)) => Detection::Correctable(err),
+        None => Detection::Uncorrectable,
+    }
+}
+
+/// Classification of a received word.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Detection {
+    /// The word is a codeword.
+    Clean,
+    /// The word is within the given number of errors, 1 or 2, of a codeword.
+    Correctable(usize),
+    /// The word has more errors than can be corrected.
+    Uncorrectable,
+}
+
+/// Try to decode the given 17-bit word, where the set bits of `erasures` mark positions
+/// known to be unreliable.
+///
+/// Erased bits carry no information, so they cost half as much of the code's distance
+/// as errors: any combination of `e` errors and `f` erasures with 2e + f ≤ 4 is
+/// corrected, up to 4 erasures with no errors.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 9 data
+/// bits and `err` is the number of corrected errors outside of the erased positions.
+/// Otherwise, return `None` to indicate an unrecoverable word.
+pub fn decode_with_erasures(word: u32, erasures: u32) -> Option<(u16, usize)> {
+    assert_eq!(word >> 17, 0);
+    assert_eq!(erasures >> 17, 0);
+
+    let f = erasures.count_ones() as usize;
+
+    if f > 4 {
+        return None;
+    }
+
+    // Filling the erasures with all zeros or all ones gets at least half of them right,
+    // which leaves at most 2 errors for one of the fillings.
+    [word & !erasures, word | erasures].iter()
+        .filter_map(|&w| decode(w))
+        .map(|(data, _)| (data, ((encode(data) ^ word) & !erasures).count_ones() as usize))
+        .filter(|&(_, err)| 2 * err + f <= 4)
+        .min_by_key(|&(_, err)| err)
+}
+
+/// Find every codeword within the given Hamming distance of the given 17-bit word.
+///
+/// Return the `(data, distance)` of each, closest first and then in order of data. With
+/// a radius beyond 2, a word can be near several codewords, so when the bounded-distance
+/// decoder fails, a higher layer with its own check, such as a CRC over the message, can
+/// try each candidate rather than dropping the frame.
+pub fn decode_list(word: u32, radius: u32) -> Vec<(u16, u32)> {
     assert_eq!(word >> 17, 0);
 
-    // Go through a full cycle of the codeword, so the data bits end up in their original
-    // position.
-    let (fixed, word) = (0..17).fold((Some(0), word), |(fixed, word), _| {
-        let syndrome: u8 = matrix_mul(word, PAR);
+    let mut list = codewords_with_data()
+        .map(|(data, w)| (data, hamming_distance(word, w)))
+        .filter(|&(_, dist)| dist <= radius)
+        .collect::<Vec<_>>();
+
+    list.sort_by_key(|&(data, dist)| (dist, data));
+    list
+}
+
+/// Decode the given 17 soft bits to the most likely codeword.
+///
+/// The soft bits are log-likelihood ratios in transmission order, so `llrs[0]` is the
+/// MSB, with positive values favoring a 0 bit and negative values favoring a 1 bit. The
+/// codeword with the least total reliability of disagreeing bits is chosen, which is
+/// the maximum-likelihood decision and can recover words with more than 2 errors when
+/// they fall on unreliable bits.
+///
+/// This is [`soft::decode`](soft/fn.decode.html) with the cost converted back to LLR
+/// units, so the LLRs are clamped to `±soft::FLOAT_LIMIT`, the cost is quantized to
+/// steps of `1 / soft::FLOAT_SCALE`, and NaN LLRs are treated as erasures.
+///
+/// Return `Some((data, cost))`, where `data` is the 9 data bits and `cost` is the summed
+/// LLR magnitude of the disagreeing bits. See the [`soft`](soft/index.html) module for
+/// integer soft bits and the extended codes.
+pub fn decode_soft(llrs: &[f32; 17]) -> Option<(u16, f32)> {
+    soft::decode(llrs).map(|(data, cost)| (data, cost as f32 / soft::FLOAT_SCALE))
+}
+
+/// Try to decode a 17-bit word from a window of received bits, tolerating symbol clock
+/// slips of up to `max_slip` bits in either direction.
+///
+/// The window holds the most recently received bit in the LSB, and the word is
+/// expected at bits `pos..pos + 17`. Each offset from `-max_slip` to `max_slip` that
+/// fits in the window is decoded, preferring the fewest corrected errors and then the
+/// smallest slip.
+///
+/// If any offset decoded, return `Some((data, err, offset))`, where `offset` is the
+/// number of bits the word was found above `pos`, so a positive offset means the word
+/// arrived early. Otherwise, return `None`.
+///
+/// Because the code is cyclic, a word slipped by one bit is a rotated codeword whenever
+/// the bit shifted in matches the bit shifted out, so neighboring bits can form a valid
+/// codeword at the wrong offset. Ties are broken toward the smallest slip, and
+/// `max_slip` should be kept as small as the clock tolerance allows.
+pub fn decode_slipped(window: u64, pos: u32, max_slip: u32) -> Option<(u16, usize, i32)> {
+    assert!(pos <= 64 - 17);
+
+    let max_slip = max_slip.min(64 - 17);
+    let lo = pos.saturating_sub(max_slip);
+    let hi = pos.saturating_add(max_slip).min(64 - 17);
+
+    (lo..hi + 1)
+        .filter_map(|p| {
+            let off = p as i32 - pos as i32;
+            decode((window >> p) as u32 & 0x1ffff).map(|(data, err)| (data, err, off))
+        })
+        .min_by_key(|&(_, err, off)| (err, off.abs()))
+}
+
+/// Encode the given 9 data bits into an 18-bit extended codeword, with the overall
+/// parity bit in the LSB.
+pub const fn encode_extended(data: u16) -> u32 {
+    extend(encode(data))
+}
+
+/// Try to decode the given 18-bit extended word, correcting up to 2 errors and
+/// detecting 3.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 9 data
+/// bits and `err` is the number of corrected bits, including the parity bit. Otherwise,
+/// return `None` to indicate an unrecoverable error.
+pub fn decode_extended(word: u32) -> Option<(u16, usize)> {
+    assert_eq!(word >> 18, 0);
+
+    let (data, _) = decode(word >> 1)?;
+
+    // With a minimum distance of 6, a word with 3 errors is at least 3 away from every
+    // extended codeword, including whichever one the base decoder chose.
+    match (encode_extended(data) ^ word).count_ones() as usize {
+        err if err <= 2 => Some((data, err)),
+        _ => None,
+    }
+}
+
+/// Encode the given 7 data bits into a 16-bit DMR quadrature residue codeword.
+pub const fn encode_dmr(data: u8) -> u16 {
+    assert!(data >> 7 == 0);
+    encode_extended(data as u16) as u16
+}
+
+/// Try to decode the given 16-bit DMR quadrature residue word, correcting up to 2
+/// errors and detecting 3.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 7 data
+/// bits and `err` is the number of corrected bits, including the parity bit. Otherwise,
+/// return `None` to indicate an unrecoverable error.
+pub fn decode_dmr(word: u16) -> Option<(u8, usize)> {
+    // The deleted MSBs are known to be zero, so a correction into them can't come from a
+    // DMR codeword.
+    match decode_extended(word as u32) {
+        Some((data, err)) if data >> 7 == 0 => Some((data as u8, err)),
+        _ => None,
+    }
+}
+
+/// Encode the given 8 data bits into a 16-bit P25 shortened cyclic codeword.
+pub const fn encode_p25(data: u8) -> u16 {
+    // The deleted MSB is zero, so it drops off the 17-bit codeword.
+    encode(data as u16) as u16
+}
+
+/// Try to decode the given 16-bit P25 shortened cyclic word, correcting up to 2 errors.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 8 data
+/// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
+/// an unrecoverable error.
+pub fn decode_p25(word: u16) -> Option<(u8, usize)> {
+    match decode(word as u32) {
+        // A correction into the deleted bit can't come from a P25 codeword.
+        Some((data, err)) if data >> 8 == 0 => Some((data as u8, err)),
+        _ => None,
+    }
+}
+
+/// Iterate over all 512 codewords, in order of their data words.
+pub fn codewords() -> impl Iterator<Item = u32> {
+    (0..1 << 9).map(encode)
+}
+
+/// Iterate over all 512 codewords as in [`codewords`](fn.codewords.html), each paired
+/// with its 9 data bits as `(data, word)`.
+pub fn codewords_with_data() -> impl Iterator<Item = (u16, u32)> {
+    (0..1 << 9).map(|data| (data, encode(data)))
+}
+
+/// Verify the constant tables used by the encoder and decoder.
+///
+/// Each table is read back from memory and checked against values re-derived from the
+/// generator polynomial, to detect corrupted flash or a bad port. This is relatively
+/// cheap and intended to be run once at startup.
+pub fn verify_tables() -> Result<(), TableError> {
+    if !check_gen(&GEN) {
+        return Err(TableError::Generator);
+    }
+
+    if !check_par(&PAR) {
+        return Err(TableError::ParityCheck);
+    }
+
+    if !check_patterns(&PATTERNS) {
+        return Err(TableError::Patterns);
+    }
+
+    #[cfg(feature = "encode-table")]
+    {
+        if !check_codewords(&CODEWORDS) {
+            return Err(TableError::Codewords);
+        }
+    }
+
+    Ok(())
+}
+
+/// Identifies a constant table that failed verification.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TableError {
+    /// The generator matrix doesn't match the generator polynomial.
+    Generator,
+    /// The parity-check matrix doesn't match the generator polynomial.
+    ParityCheck,
+    /// The syndrome/pattern table doesn't match the parity-check matrix.
+    Patterns,
+    /// The encoding table doesn't match the generator polynomial.
+    Codewords,
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match *self {
+            TableError::Generator => "generator matrix is corrupt",
+            TableError::ParityCheck => "parity-check matrix is corrupt",
+            TableError::Patterns => "syndrome table is corrupt",
+            TableError::Codewords => "encoding table is corrupt",
+        })
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// Exhaustively check the encoder and decoder, along with the constant tables as in
+/// [`verify_tables`](fn.verify_tables.html).
+///
+/// Every data word is encoded and checked against polynomial division, and its codeword
+/// is decoded with no errors and with every 1- and 2-bit error pattern. This covers the
+/// whole correction capability of the code in well under a second, for a tamper or
+/// corruption check at startup or in a downstream test suite.
+pub fn verify_code() -> Result<(), VerifyError> {
+    verify_tables().map_err(VerifyError::Table)?;
 
-        if syndrome == 0 {
-            return (fixed, rotate_17(word));
+    for (data, word) in codewords_with_data() {
+        if word != (data as u32) << 8 | poly_parity(data) {
+            return Err(VerifyError::Encode { data });
         }
 
-        match PATTERNS[syndrome as usize] {
-            0 => (None, rotate_17(word)),
-            pat => (Some(pat.count_ones() as usize), rotate_17(word ^ pat)),
+        let pats = std::iter::once(0)
+            .chain((0..17).flat_map(|i| (i..17).map(move |j| 1 << i | 1 << j)));
+
+        for pat in pats {
+            if decode(word ^ pat) != Some((data, pat.count_ones() as usize)) {
+                return Err(VerifyError::Decode { word: word ^ pat });
+            }
         }
-    });
+    }
+
+    Ok(())
+}
+
+/// Describes a failure found by [`verify_code`](fn.verify_code.html).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// A constant table failed verification.
+    Table(TableError),
+    /// The given data word encoded to the wrong codeword.
+    Encode {
+        /// The 9 data bits.
+        data: u16,
+    },
+    /// The given word, within 2 errors of a codeword, decoded to the wrong result.
+    Decode {
+        /// The 17-bit received word.
+        word: u32,
+    },
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            VerifyError::Table(e) => write!(f, "{}", e),
+            VerifyError::Encode { data } => write!(f, "wrong codeword for data {:#05x}", data),
+            VerifyError::Decode { word } => write!(f, "wrong decode of word {:#07x}", word),
+        }
+    }
+}
 
-    fixed.map(|err| ((word >> 8) as u16, err))
+impl std::error::Error for VerifyError {}
+
+/// The base (17, 9, 5) cyclic code, for use with the generic [`code`](code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cyclic;
+
+impl Encoder for Cyclic {
+    type Data = u16;
+    type Codeword = u32;
+
+    fn encode(&self, data: u16) -> u32 { encode(data) }
+}
+
+impl Decoder for Cyclic {
+    fn decode(&self, word: u32) -> Option<(u16, usize)> { decode(word) }
+}
+
+impl BlockCode for Cyclic {
+    const N: usize = 17;
+    const K: usize = 9;
+    const D: usize = 5;
 }
 
+impl_dyn_block_code!(Cyclic, "cyclic17", u16, 9, u32, 17);
+
 /// Transpose of the generator matrix, without the identity part.
-const GEN: &[u16] = &[
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static GEN: [u16; 8] = [
     0b100111100,
     0b010011110,
     0b001001111,
@@ -73,87 +864,535 @@ const GEN: &[u16] = &[
     0b001111001,
 ];
 
+/// Maps each data word to its codeword.
+#[cfg(feature = "encode-table")]
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static CODEWORDS: [u32; 512] = build_codewords();
+
 /// Transpose of parity-check matrix.
 ///
-/// This was derived in the standard way from the generator matrix.
-const PAR: &[u32] = &[
-    0b10011110010000000,
-    0b01001111001000000,
-    0b00100111100100000,
-    0b10001101100010000,
-    0b11011000100001000,
-    0b11110010000000100,
-    0b01111001000000010,
-    0b00111100100000001,
-];
+/// This is derived in the standard way from the generator matrix, as `[P | I]`.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static PAR: [u32; 8] = build_par();
 
 /// Maps each 8-bit syndrome to an error pattern.
 ///
-/// If a syndrome is invalid, the pattern is zero. Because the code is cyclic, we only
-/// need to store patterns for syndromes where the LSB is set.
-const PATTERNS: [u32; 256] = [
-    0,
-    0b00000000000000001,
-    0,
-    0b00000000000000011,
-    0,
-    0b00000000000000101,
-    0, 0, 0,
-    0b00000000000001001,
-    0, 0, 0, 0, 0, 0, 0,
-    0b00000000000010001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000000000100001,
-    0, 0, 0, 0,
-    0b00100000000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000000100000001,
-    0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000000001000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b01000000000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0,
-    0b00000001000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000000010000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00010000000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b10000000000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0,
-    0b00001000000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000010000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0b00000100000000001,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-];
+/// If a syndrome is invalid, the pattern is zero. Every correctable pattern is stored,
+/// including all rotations, so a word is corrected with a single lookup rather than
+/// checking each of its 17 rotations against the patterns with the LSB set.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static PATTERNS: [u32; 256] = build_patterns();
 
-/// Cyclically rotate the word right as if it was 17 bits long.
-fn rotate_17(word: u32) -> u32 {
-    let lsb = word & 1;
-    word >> 1 | lsb << 16
-}
+// Every codeword must have a zero syndrome under the derived parity-check matrix.
+const _: () = {
+    let mut data = 0;
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    while data < 1 << 9 {
+        assert!(const_syndrome(encode(data)) == 0, "parity-check matrix doesn't match");
+        data += 1;
+    }
+};
 
-    #[test]
-    fn test_encode() {
-        assert_eq!(encode(0b000000000), 0b000000000_00000000);
-        assert_eq!(encode(0b111111111), 0b111111111_11111111);
-        assert_eq!(encode(0b100000001), 0b100000001_10100101);
-        assert_eq!(encode(0b000001001), 0b000001001_11001000);
-        assert_eq!(encode(0b000001011), 0b000001011_10111010);
-        assert_eq!(encode(0b000001010), 0b000001010_10000011);
-        assert_eq!(encode(0b000001000), 0b000001000_11110001);
+/// Generator polynomial, with the x<sup>8</sup> term in the MSB.
+const GEN_POLY: u32 = 0b100111001;
+
+/// Codeword for each single data bit, counted from the LSB.
+const GEN_COLUMNS: [u32; 9] = {
+    let mut cols = [0; 9];
+    let mut bit = 0;
+
+    while bit < 9 {
+        cols[bit] = matrix_encode(1 << bit);
+        bit += 1;
     }
 
-    #[test]
+    cols
+};
+
+/// Encode the given data bits by multiplying with the generator matrix.
+///
+/// Same as `matrix_mul_systematic(data, &GEN)`, which isn't usable in a const fn.
+const fn matrix_encode(data: u16) -> u32 {
+    let mut parity = 0;
+    let mut row = 0;
+
+    while row < GEN.len() {
+        parity = parity << 1 | (GEN[row] & data).count_ones() & 1;
+        row += 1;
+    }
+
+    (data as u32) << 8 | parity
+}
+
+/// Build the encoding table from the generator matrix.
+#[cfg(feature = "encode-table")]
+const fn build_codewords() -> [u32; 512] {
+    let mut table = [0; 512];
+    let mut data = 0;
+
+    while data < 512 {
+        table[data] = matrix_encode(data as u16);
+        data += 1;
+    }
+
+    table
+}
+
+/// Build the parity-check matrix by appending an identity column to each row of the
+/// generator matrix.
+const fn build_par() -> [u32; 8] {
+    let mut par = [0; 8];
+    let mut row = 0;
+
+    while row < 8 {
+        par[row] = (GEN[row] as u32) << 8 | 1 << (7 - row);
+        row += 1;
+    }
+
+    par
+}
+
+/// Compute the syndrome of the given word with the derived parity-check matrix.
+///
+/// Same as `matrix_mul(word, &PAR)`, which isn't usable in a const fn.
+const fn const_syndrome(word: u32) -> u8 {
+    let par = build_par();
+    let mut syndrome = 0;
+    let mut row = 0;
+
+    while row < 8 {
+        syndrome = syndrome << 1 | ((par[row] & word).count_ones() & 1) as u8;
+        row += 1;
+    }
+
+    syndrome
+}
+
+/// Build the syndrome table from every correctable error pattern.
+const fn build_patterns() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+
+    while i < 17 {
+        let mut j = i;
+
+        while j < 17 {
+            let pat = 1 << i | 1 << j;
+            let syndrome = const_syndrome(pat) as usize;
+
+            assert!(table[syndrome] == 0, "correctable patterns share a syndrome");
+            table[syndrome] = pat;
+
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+/// Read the given table entry from memory, even if the compiler could otherwise fold it
+/// into a constant.
+pub(crate) fn load<T: Copy>(entry: &T) -> T {
+    // This is safe because the reference is valid and aligned.
+    unsafe { std::ptr::read_volatile(entry) }
+}
+
+/// Compute the 8 parity bits for the given data bits by polynomial division.
+fn poly_parity(data: u16) -> u32 {
+    (0..9).rev().fold((data as u32) << 8, |rem, bit| {
+        if rem >> (bit + 8) & 1 == 1 { rem ^ GEN_POLY << bit } else { rem }
+    })
+}
+
+/// Check the given generator matrix against the generator polynomial.
+fn check_gen(gen: &[u16]) -> bool {
+    gen.len() == 8 && gen.iter().enumerate().all(|(r, row)| {
+        let expected = (0..9).fold(0, |row, i| {
+            row | ((poly_parity(1 << i) >> (7 - r) & 1) as u16) << i
+        });
+
+        load(row) == expected
+    })
+}
+
+/// Check the given parity-check matrix against the generator matrix derived from the
+/// generator polynomial.
+fn check_par(par: &[u32]) -> bool {
+    par.len() == 8 && par.iter().enumerate().all(|(r, row)| {
+        let expected = (0..9).fold(1 << (7 - r), |row, i| {
+            row | (poly_parity(1 << i) >> (7 - r) & 1) << (i + 8)
+        });
+
+        load(row) == expected
+    })
+}
+
+/// Check the given syndrome table against every correctable pattern.
+fn check_patterns(patterns: &[u32]) -> bool {
+    let pats = (0..17).flat_map(|i| (i..17).map(move |j| 1 << i | 1 << j));
+
+    let valid = pats.clone().all(|pat| {
+        let syndrome: u8 = matrix_mul(pat, &PAR);
+        load(&patterns[syndrome as usize]) == pat
+    });
+
+    // Every other entry must be empty.
+    valid && patterns.len() == 256 &&
+        patterns.iter().filter(|&p| load(p) != 0).count() == pats.count()
+}
+
+/// Check the given encoding table against the generator polynomial.
+#[cfg(feature = "encode-table")]
+fn check_codewords(codewords: &[u32]) -> bool {
+    codewords.len() == 512 && codewords.iter().enumerate().all(|(data, word)| {
+        load(word) == (data as u32) << 8 | poly_parity(data as u16)
+    })
+}
+
+/// Check the result of an accelerated decoder against the scalar decoder, panicking with
+/// the backend name, the word, both results, and any extra backend context if they
+/// differ.
+#[cfg(feature = "cross-check")]
+pub(crate) fn cross_check(backend: &str, word: u32, actual: Option<(u16, usize)>,
+                          context: std::fmt::Arguments)
+{
+    let expected = decode_diagnosed(word).ok();
+
+    assert!(actual == expected,
+            "{} decoder diverged for word {:#07x}: got {:?}, scalar decoder got {:?} ({})",
+            backend, word, actual, expected, context);
+}
+
+/// Append the overall parity bit to the given 17-bit codeword.
+const fn extend(word: u32) -> u32 {
+    word << 1 | word.count_ones() & 1
+}
+
+/// Cyclically rotate the word right as if it was 17 bits long.
+fn rotate_17(word: u32) -> u32 {
+    let lsb = word & 1;
+    word >> 1 | lsb << 16
+}
+
+#[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
+mod test {
+    use super::*;
+    use binfield_matrix::matrix_mul_systematic;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(0b000000000), 0b000000000_00000000);
+        assert_eq!(encode(0b111111111), 0b111111111_11111111);
+        assert_eq!(encode(0b100000001), 0b100000001_10100101);
+        assert_eq!(encode(0b000001001), 0b000001001_11001000);
+        assert_eq!(encode(0b000001011), 0b000001011_10111010);
+        assert_eq!(encode(0b000001010), 0b000001010_10000011);
+        assert_eq!(encode(0b000001000), 0b000001000_11110001);
+    }
+
+    #[test]
+    fn test_extended() {
+        for (data, w) in codewords().enumerate() {
+            let data = data as u16;
+            let x = encode_extended(data);
+
+            assert_eq!(x >> 1, w);
+            assert_eq!(x.count_ones() % 2, 0);
+            assert_eq!(decode_extended(x), Some((data, 0)));
+
+            for i in 0..18 {
+                assert_eq!(decode_extended(x ^ 1 << i), Some((data, 1)));
+
+                for j in i + 1..18 {
+                    assert_eq!(decode_extended(x ^ 1 << i ^ 1 << j), Some((data, 2)));
+                }
+            }
+        }
+
+        // Every 3-error pattern is detected.
+        let x = encode_extended(0b011011011);
+
+        for i in 0..18 {
+            for j in i + 1..18 {
+                for k in j + 1..18 {
+                    assert_eq!(decode_extended(x ^ 1 << i ^ 1 << j ^ 1 << k), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_dmr() {
+        let expected = [
+            0x0000, 0x0273, 0x04e5, 0x0696, 0x09c9, 0x0bba, 0x0d2c, 0x0f5f,
+            0x11e2, 0x1391, 0x1507, 0x1774, 0x182b, 0x1a58, 0x1cce, 0x1ebd,
+        ];
+
+        for (data, &word) in expected.iter().enumerate() {
+            assert_eq!(encode_dmr(data as u8), word);
+        }
+
+        // The parity bit is the parity of the data bits under the mask 1010111.
+        for data in 0..1 << 7 {
+            let parity = (data & 0b1010111u8).count_ones() as u16 & 1;
+            assert_eq!(encode_dmr(data) & 1, parity);
+        }
+    }
+
+    #[test]
+    fn test_decode_dmr() {
+        for data in 0..1 << 7 {
+            let w = encode_dmr(data);
+
+            assert_eq!(decode_dmr(w), Some((data, 0)));
+
+            for i in 0..16 {
+                assert_eq!(decode_dmr(w ^ 1 << i), Some((data, 1)));
+
+                for j in i + 1..16 {
+                    assert_eq!(decode_dmr(w ^ 1 << i ^ 1 << j), Some((data, 2)));
+
+                    // Every 3-error pattern is detected rather than miscorrected.
+                    for k in j + 1..16 {
+                        assert_eq!(decode_dmr(w ^ 1 << i ^ 1 << j ^ 1 << k), None);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_p25() {
+        // These were worked by long division of the data times x^8 by g(x), rather than
+        // taken from TIA-102.BAAA or another implementation: no published vectors for the
+        // P25 code could be verified, so they only pin the mapping to the polynomial.
+        assert_eq!(encode_p25(0b00000000), 0b00000000_00000000);
+        assert_eq!(encode_p25(0b00000001), 0b00000001_00111001);
+        assert_eq!(encode_p25(0b00001001), 0b00001001_11001000);
+        assert_eq!(encode_p25(0b00001011), 0b00001011_10111010);
+        assert_eq!(encode_p25(0b11111111), 0b11111111_01100011);
+
+        for data in 0..=255 {
+            assert_eq!(encode_p25(data) as u32, encode(data as u16));
+            assert_eq!(encode_p25(data) as u32 & 0xff, poly_parity(data as u16));
+        }
+    }
+
+    #[test]
+    fn test_decode_p25() {
+        for data in 0..=255 {
+            let w = encode_p25(data);
+
+            assert_eq!(decode_p25(w), Some((data, 0)));
+
+            for i in 0..16 {
+                assert_eq!(decode_p25(w ^ 1 << i), Some((data, 1)));
+
+                for j in i + 1..16 {
+                    assert_eq!(decode_p25(w ^ 1 << i ^ 1 << j), Some((data, 2)));
+                }
+            }
+        }
+
+        // Words that only decode by flipping the deleted bit are rejected.
+        let w = (encode(0b100000000) ^ 1 << 16 ^ 1) as u16;
+        assert_eq!(decode(w as u32), Some((0b100000000, 2)));
+        assert_eq!(decode_p25(w), None);
+    }
+
+    #[test]
+    fn test_fallible() {
+        assert_eq!(try_encode(0b100000001), Ok(0b100000001_10100101));
+        assert_eq!(try_encode(1 << 9), Err(CodeError::InputOutOfRange));
+
+        let w = encode(0b100000001);
+        assert_eq!(try_decode(w ^ 0b11), Ok(Decoded { data: 0b100000001, errors: 2 }));
+        assert_eq!(try_decode(0x1f), Err(CodeError::Uncorrectable));
+        assert_eq!(try_decode(1 << 17), Err(CodeError::InputOutOfRange));
+        assert_eq!(try_decode(!0), Err(CodeError::InputOutOfRange));
+
+        assert_eq!(CodeError::Uncorrectable.to_string(), "uncorrectable word");
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(weight(0), 0);
+        assert_eq!(weight(0x1ffff), 17);
+        assert_eq!(hamming_distance(0b1011, 0b0110), 3);
+        assert_eq!(hamming_distance(0x1ffff, 0), 17);
+
+        // The minimum distance is 5.
+        assert!(codewords().skip(1).all(|w| hamming_distance(w, encode(0)) >= 5));
+        assert!(codewords().any(|w| weight(w) == 5));
+
+        let w = encode(0b110010011);
+        assert_eq!(distance_to_codeword(w, 0b110010011), 0);
+        assert_eq!(distance_to_codeword(w ^ 0b10001, 0b110010011), 2);
+
+        for (i, c) in codewords().enumerate() {
+            let word = c ^ 0b101 << (i % 15);
+            let (d, err) = decode(word).unwrap();
+            assert_eq!(distance_to_codeword(word, d), err as u32);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weight_range() {
+        weight(1 << 17);
+    }
+
+    #[test]
+    fn test_reencode_flip() {
+        for (data, w) in codewords_with_data() {
+            for bit in 0..9 {
+                assert_eq!(reencode_flip(w, bit), encode(data ^ 1 << bit));
+            }
+        }
+
+        // Errors pass through untouched.
+        let w = encode(0b111000000) ^ 0b101;
+        assert_eq!(reencode_flip(w, 8), encode(0b011000000) ^ 0b101);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_reencode_flip_range() {
+        reencode_flip(0, 9);
+    }
+
+    #[test]
+    fn test_masked() {
+        assert_eq!(encode_masked(0b100000001), encode(0b100000001));
+        assert_eq!(encode_masked(0b1111110_100000001), encode(0b100000001));
+        assert_eq!(encode_masked(!0), encode(0x1ff));
+
+        let w = encode(0b100000001);
+        assert_eq!(decode_masked(w), Some((0b100000001, 0)));
+        assert_eq!(decode_masked(0xabc << 17 | w ^ 0b11), Some((0b100000001, 2)));
+        assert_eq!(decode_masked(!0 << 17 | 0x1f), None);
+    }
+
+    #[test]
+    fn test_const_encode() {
+        const WORDS: [u32; 3] = [encode(0), encode(0b100000001), encode(0b000001011)];
+        const DMR: u16 = encode_dmr(0b0000011);
+        const P25: u16 = encode_p25(0b00000001);
+
+        assert_eq!(WORDS, [0, 0b100000001_10100101, 0b000001011_10111010]);
+        assert_eq!(DMR, 0x0696);
+        assert_eq!(P25, 0b00000001_00111001);
+
+        for data in 0..1 << 9 {
+            assert_eq!(encode(data), matrix_mul_systematic::<u16, u32>(data, &GEN));
+        }
+    }
+
+    #[test]
+    fn test_encode_traced() {
+        assert_eq!(encode_traced(0), [0; 9]);
+
+        // A lone MSB loads the feedback taps, which are then shifted and reduced.
+        assert_eq!(encode_traced(0b100000000), [
+            0b00111001,
+            0b01110010,
+            0b11100100,
+            0b11110001,
+            0b11011011,
+            0b10001111,
+            0b00100111,
+            0b01001110,
+            0b10011100,
+        ]);
+
+        for d in 0..1 << 9 {
+            assert_eq!(encode_traced(d)[8] as u32, encode(d) & 0xff);
+        }
+    }
+
+    #[test]
+    fn test_newtypes() {
+        assert_eq!(Data9::new(1 << 9), None);
+        assert_eq!(Codeword17::new(1 << 17), None);
+        assert_eq!(Data9::try_from(0x200), Err(CodeError::InputOutOfRange));
+        assert_eq!(Codeword17::try_from(0x20000), Err(CodeError::InputOutOfRange));
+
+        for d in 0..1 << 9 {
+            let data = Data9::new(d).unwrap();
+            let word = data.encode();
+
+            assert_eq!(u32::from(word), encode(d));
+            assert_eq!(word.data(), data);
+            assert_eq!(word.parity() as u32, encode(d) & 0xff);
+            assert_eq!(word.decode(), Some((data, 0)));
+
+            let rx = Codeword17::try_from(word.get() ^ 0b1000001).unwrap();
+            assert_eq!(rx.decode(), Some((data, 2)));
+            assert_eq!(u16::from(rx.decode().unwrap().0), d);
+        }
+
+        assert_eq!(Codeword17::new(0x1f).unwrap().decode(), None);
+    }
+
+    #[test]
+    fn test_slice() {
+        let data = (0..1 << 9).collect::<Vec<u16>>();
+        let mut words = vec![0; data.len()];
+
+        encode_slice(&data, &mut words);
+        assert!(data.iter().zip(&words).all(|(&d, &w)| w == encode(d)));
+
+        // Corrupt some words beyond repair.
+        for (i, w) in words.iter_mut().enumerate() {
+            *w ^= [0, 0b1, 0b101, 0b11111][i % 4];
+        }
+
+        let mut out = vec![None; words.len()];
+        decode_slice(&words, &mut out);
+
+        assert!(words.iter().zip(&out).all(|(&w, &r)| r == decode(w)));
+        assert!(out.iter().any(Option::is_none));
+
+        encode_slice(&[], &mut []);
+        decode_slice(&[], &mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_mismatch() {
+        decode_slice(&[0; 2], &mut [None; 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_par_slice() {
+        let data = (0..20000).map(|i| (i * 7 % 512) as u16).collect::<Vec<_>>();
+        let mut words = vec![0; data.len()];
+        let mut expected = vec![0; data.len()];
+
+        par_encode_slice(&data, &mut words);
+        encode_slice(&data, &mut expected);
+        assert_eq!(words, expected);
+
+        for (i, w) in words.iter_mut().enumerate() {
+            *w ^= [0, 0b1, 0b101, 0b11111][i % 4];
+        }
+
+        let mut out = vec![None; words.len()];
+        let mut expected = vec![None; words.len()];
+
+        par_decode_slice(&words, &mut out);
+        decode_slice(&words, &mut expected);
+        assert_eq!(out, expected);
+
+        par_encode_slice(&[], &mut []);
+        par_decode_slice(&[], &mut []);
+    }
+
+    #[test]
     fn test_decode() {
         // Exhaustively test loopback of all possible input words.
         for w in 0..1<<9 {
@@ -177,6 +1416,343 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_decode_mask() {
+        let w = encode(0b001110001);
+
+        assert_eq!(decode_mask(w), Some((0b001110001, 0)));
+        assert_eq!(decode_mask(w ^ 1 << 16), Some((0b001110001, 1 << 16)));
+        assert_eq!(decode_mask(w ^ 0b1000000100), Some((0b001110001, 0b1000000100)));
+
+        for word in (0..1 << 17).step_by(13) {
+            assert_eq!(decode_mask(word).map(|(d, m)| (d, m.count_ones() as usize)),
+                       decode(word));
+        }
+    }
+
+    #[test]
+    fn test_decode_diagnosed() {
+        let w = encode(0b110011001);
+
+        assert_eq!(decode_diagnosed(w ^ 0b101), Ok((0b110011001, 2)));
+
+        for &err in &[0b111, 0b1011, 0b10101, 0b11 << 15 | 1, 0x1ffff] {
+            let res = decode(w ^ err);
+
+            match decode_diagnosed(w ^ err) {
+                Ok(ok) => assert_eq!(Some(ok), res),
+                Err(fail) => {
+                    assert_eq!(res, None);
+                    assert_eq!(fail.syndrome, syndrome(w ^ err));
+                    assert!(fail.min_weight <= fail.syndrome.count_ones());
+                },
+            }
+        }
+
+        // Every uncorrectable word's syndrome is non-zero in each rotation.
+        let fails = (0..1 << 17).step_by(31).filter_map(|w| decode_diagnosed(w).err());
+        assert!(fails.clone().all(|f| f.syndrome != 0 && f.min_weight >= 3));
+        assert!(fails.clone().any(|f| f.min_weight == 3));
+    }
+
+    #[test]
+    fn test_syndrome() {
+        for w in codewords() {
+            assert_eq!(syndrome(w), 0);
+            assert!(is_codeword(w));
+
+            // The syndrome only depends on the error pattern.
+            assert_eq!(syndrome(w ^ 0b101), syndrome(0b101));
+            assert!(!is_codeword(w ^ 1 << 16));
+        }
+
+        // Parity bit errors map straight into the syndrome.
+        for i in 0..8 {
+            assert_eq!(syndrome(1 << i), 1 << i);
+        }
+
+        assert_eq!((0..1 << 17).filter(|&w| is_codeword(w)).count(), 512);
+    }
+
+    #[test]
+    fn test_detect() {
+        let w = encode(0b110000011);
+
+        assert_eq!(detect(w), Detection::Clean);
+        assert_eq!(detect(w ^ 1 << 9), Detection::Correctable(1));
+        assert_eq!(detect(w ^ 0b11 << 14), Detection::Correctable(2));
+        assert_eq!(detect(0x1f), Detection::Uncorrectable);
+
+        for word in (0..1 << 17).step_by(11) {
+            let expected = match decode(word) {
+                Some((_, 0)) => Detection::Clean,
+                Some((_, err)) => Detection::Correctable(err),
+                None => Detection::Uncorrectable,
+            };
+
+            assert_eq!(detect(word), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_with_erasures() {
+        let w = encode(0b010011010);
+
+        assert_eq!(decode_with_erasures(w, 0), Some((0b010011010, 0)));
+        assert_eq!(decode_with_erasures(w ^ 0b11, 0), Some((0b010011010, 2)));
+        assert_eq!(decode_with_erasures(w ^ 0b111, 0), decode(w ^ 0b111));
+
+        for e in 0..17 {
+            for f in 0..17 {
+                for g in f + 1..17 {
+                    // One error and 2 erasures, whatever the erased bits hold.
+                    let erased = 1 << f | 1 << g;
+
+                    if e != f && e != g {
+                        for &junk in &[0, erased, 1 << f] {
+                            let rx = (w ^ 1 << e) & !erased | junk;
+                            assert_eq!(decode_with_erasures(rx, erased), Some((0b010011010, 1)));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Four erasures with no errors.
+        let erased = 0b1111 << 6;
+        assert_eq!(decode_with_erasures(w ^ erased, erased), Some((0b010011010, 0)));
+        assert_eq!(decode_with_erasures(w & !erased, erased), Some((0b010011010, 0)));
+
+        // Four erasures and an error is beyond the distance.
+        assert_eq!(decode_with_erasures(w ^ 1, erased), None);
+        assert_eq!(decode_with_erasures(w, 0b11111), None);
+
+        // Three erasures and an error.
+        let erased = 0b111 << 12;
+        assert_eq!(decode_with_erasures(w ^ erased ^ 1, erased), None);
+    }
+
+    #[test]
+    fn test_decode_list() {
+        let w = encode(0b010101010);
+        assert_eq!(decode_list(w, 0), [(0b010101010, 0)]);
+        assert_eq!(decode_list(w ^ 0b11, 2), [(0b010101010, 2)]);
+        assert!(decode_list(w ^ 0b111, 3).contains(&(0b010101010, 3)));
+
+        // Within 2 errors, the list agrees with the decoder.
+        for (i, c) in codewords().enumerate() {
+            let word = c ^ 0b1001 << (i % 14);
+            assert_eq!(decode_list(word, 2), [decode(word).map(|(d, e)| (d, e as u32)).unwrap()]);
+        }
+
+        // A word 3 errors from the sent codeword can be 2 from another.
+        let m = codewords().find(|w| w.count_ones() == 5).unwrap();
+        let low = (0..17).filter(|&i| m >> i & 1 == 1).take(3).fold(0, |p, i| p | 1 << i);
+        let list = decode_list(w ^ low, 3);
+
+        assert_eq!(list[0], ((0b010101010 ^ m >> 8) as u16, 2));
+        assert!(list.contains(&(0b010101010, 3)));
+        assert!(list.windows(2).all(|p| (p[0].1, p[0].0) < (p[1].1, p[1].0)));
+
+        assert_eq!(decode_list(0, 17).len(), 512);
+    }
+
+    #[test]
+    fn test_decode_soft() {
+        let to_llrs = |word: u32| {
+            let mut llrs = [0.0f32; 17];
+
+            for (i, llr) in llrs.iter_mut().enumerate() {
+                *llr = if word >> (16 - i) & 1 == 1 { -4.0 } else { 4.0 };
+            }
+
+            llrs
+        };
+
+        for (data, w) in codewords().enumerate() {
+            assert_eq!(decode_soft(&to_llrs(w)), Some((data as u16, 0.0)));
+        }
+
+        // Four weak errors are beyond the hard decoder.
+        let w = encode(0b100100100);
+        let mut llrs = to_llrs(w);
+
+        for &i in &[1, 6, 11, 15] {
+            llrs[i] = -llrs[i] / 8.0;
+        }
+
+        let hard = llrs.iter().fold(0, |w, &llr| w << 1 | (llr < 0.0) as u32);
+        assert_ne!(decode(hard).map(|(d, _)| d), Some(0b100100100));
+        assert_eq!(decode_soft(&llrs), Some((0b100100100, 2.0)));
+
+        // NaN is an erasure, which costs nothing either way.
+        llrs[3] = f32::NAN;
+        assert_eq!(decode_soft(&llrs), Some((0b100100100, 2.0)));
+    }
+
+    #[test]
+    fn test_decode_slipped() {
+        let w = encode(0b110100111) as u64;
+        let window = 0b1011_0000000000000000000_0111 | w << 7;
+
+        assert_eq!(decode_slipped(window, 7, 0), Some((0b110100111, 0, 0)));
+        assert_eq!(decode_slipped(window, 8, 2), Some((0b110100111, 0, -1)));
+        assert_eq!(decode_slipped(window, 5, 2), Some((0b110100111, 0, 2)));
+        assert_eq!(decode_slipped(window ^ 1 << 10, 6, 1), Some((0b110100111, 1, 1)));
+
+        // Offsets are limited to the window.
+        assert_eq!(decode_slipped(w, 0, 3), Some((0b110100111, 0, 0)));
+        assert_eq!(decode_slipped(w << 47, 47, 3), Some((0b110100111, 0, 0)));
+        assert_eq!(decode_slipped(w, 0, u32::MAX), Some((0b110100111, 0, 0)));
+        assert_eq!(decode_slipped(w << 47, 47, u32::MAX), Some((0b110100111, 0, 0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decode_slipped_range() {
+        decode_slipped(0, u32::MAX, 0);
+    }
+
+    #[test]
+    fn test_inverted() {
+        assert_eq!(encode(0x1ff), 0x1ffff);
+
+        for w in (0..1 << 17).step_by(13) {
+            let inv = decode(w ^ 0x1ffff);
+
+            match decode(w) {
+                Some((data, err)) => assert_eq!(inv, Some((data ^ 0x1ff, err))),
+                None => assert_eq!(inv, None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_codewords() {
+        assert_eq!(codewords().count(), 512);
+
+        for (d, w) in codewords().enumerate() {
+            assert_eq!(decode(w), Some((d as u16, 0)));
+        }
+
+        // The minimum distance of a linear code is its minimum nonzero weight.
+        assert_eq!(codewords().skip(1).map(u32::count_ones).min(), Some(5));
+
+        assert!(codewords_with_data().map(|(_, w)| w).eq(codewords()));
+        assert!(codewords_with_data().all(|(d, w)| decode(w) == Some((d, 0))));
+    }
+
+    #[test]
+    fn test_verify_code() {
+        assert_eq!(verify_code(), Ok(()));
+
+        assert_eq!(VerifyError::Table(TableError::Patterns).to_string(),
+                   "syndrome table is corrupt");
+        assert_eq!(VerifyError::Decode { word: 0x1f }.to_string(),
+                   "wrong decode of word 0x0001f");
+    }
+
+    #[test]
+    fn test_verify_tables() {
+        assert_eq!(verify_tables(), Ok(()));
+
+        let mut gen = GEN;
+        gen[3] ^= 0b100;
+        assert!(!check_gen(&gen));
+
+        let mut par = PAR;
+        par[7] ^= 1 << 12;
+        assert!(!check_par(&par));
+
+        let mut pats = PATTERNS;
+        pats[5] = 0;
+        assert!(!check_patterns(&pats));
+
+        let mut pats = PATTERNS;
+        pats[2] = 0b100;
+        assert!(!check_patterns(&pats));
+    }
+
+    #[test]
+    fn test_derived_tables() {
+        // The hand-written tables the derived ones replaced.
+        const OLD_PAR: [u32; 8] = [
+        0b10011110010000000,
+        0b01001111001000000,
+        0b00100111100100000,
+        0b10001101100010000,
+        0b11011000100001000,
+        0b11110010000000100,
+        0b01111001000000010,
+        0b00111100100000001,
+        ];
+
+        // Only the patterns with the LSB set, since the decoder used to rotate each word.
+        const OLD_PATTERNS: [u32; 256] = [
+        0,
+        0b00000000000000001,
+        0,
+        0b00000000000000011,
+        0,
+        0b00000000000000101,
+        0, 0, 0,
+        0b00000000000001001,
+        0, 0, 0, 0, 0, 0, 0,
+        0b00000000000010001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000000000100001,
+        0, 0, 0, 0,
+        0b00100000000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000000100000001,
+        0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000000001000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b01000000000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0,
+        0b00000001000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000000010000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00010000000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b10000000000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0,
+        0b00001000000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000010000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0b00000100000000001,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+
+        assert_eq!(PAR, OLD_PAR);
+
+        for (s, &pat) in OLD_PATTERNS.iter().enumerate() {
+            if pat != 0 {
+                assert_eq!(pat & 1, 1);
+                assert_eq!(PATTERNS[s], pat);
+            }
+        }
+
+        assert_eq!(OLD_PATTERNS.iter().filter(|&&pat| pat != 0).count(), 17);
+    }
+
+    #[cfg(feature = "encode-table")]
+    #[test]
+    fn test_encode_table() {
+        for data in 0..512 {
+            assert_eq!(CODEWORDS[data as usize], matrix_encode(data));
+        }
+
+        let mut codewords = CODEWORDS;
+        codewords[37] ^= 1;
+        assert!(!check_codewords(&codewords));
+    }
+
     #[test]
     fn test_rotate_17() {
         assert_eq!(rotate_17(0b00000000000000000), 0b00000000000000000);
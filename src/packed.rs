@@ -0,0 +1,163 @@
+//! Byte payloads carried as a packed stream of codewords.
+//!
+//! The payload bits, MSB first, are split into 9-bit chunks, with the final chunk
+//! padded with zero bits if needed. Each chunk is encoded into a 17-bit codeword, and
+//! the codewords are packed back to back, MSB first, into a byte stream, with the final
+//! byte padded with zero bits. The stream therefore carries no length field, and the
+//! decoder has to be given the payload length to undo the padding.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::packed;
+//!
+//! let payload = b"hello";
+//! let mut stream = packed::encode(payload);
+//! assert_eq!(stream.len(), packed::encoded_len(payload.len()));
+//!
+//! stream[1] ^= 0b100;
+//! stream[4] ^= 0b1010;
+//!
+//! assert_eq!(packed::decode(&stream, payload.len()), Ok((payload.to_vec(), 3)));
+//! ```
+
+/// Error decoding a packed stream.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// The stream has the wrong number of bytes for the payload length.
+    Length,
+    /// The codeword at the given index couldn't be decoded.
+    Uncorrectable(usize),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            FrameError::Length => write!(f, "stream length doesn't match payload length"),
+            FrameError::Uncorrectable(i) => write!(f, "codeword {} is uncorrectable", i),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Number of codewords carrying a payload of the given number of bytes.
+pub fn codeword_count(payload_len: usize) -> usize {
+    (payload_len * 8).div_ceil(9)
+}
+
+/// Number of stream bytes carrying a payload of the given number of bytes.
+pub fn encoded_len(payload_len: usize) -> usize {
+    (codeword_count(payload_len) * 17).div_ceil(8)
+}
+
+/// Encode the given payload into a packed stream of codewords.
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0; encoded_len(payload.len())];
+
+    for i in 0..codeword_count(payload.len()) {
+        let data = read_bits(payload, i * 9, 9);
+        write_bits(&mut stream, i * 17, 17, ::encode(data as u16));
+    }
+
+    stream
+}
+
+/// Decode the given packed stream, carrying a payload of the given number of bytes.
+///
+/// If every codeword was decoded, return `Ok((payload, err))`, where `err` is the total
+/// number of corrected bits. Otherwise, return the first problem found.
+pub fn decode(stream: &[u8], payload_len: usize) -> Result<(Vec<u8>, usize), FrameError> {
+    if stream.len() != encoded_len(payload_len) {
+        return Err(FrameError::Length);
+    }
+
+    // Includes room for the padding bits of the final chunk.
+    let mut payload = vec![0; (codeword_count(payload_len) * 9).div_ceil(8)];
+    let mut total = 0;
+
+    for i in 0..codeword_count(payload_len) {
+        let word = read_bits(stream, i * 17, 17);
+        let (data, err) = ::decode(word).ok_or(FrameError::Uncorrectable(i))?;
+
+        write_bits(&mut payload, i * 9, 9, data as u32);
+        total += err;
+    }
+
+    payload.truncate(payload_len);
+
+    Ok((payload, total))
+}
+
+/// Read the given number of bits, up to 32, starting at the given bit offset, MSB
+/// first. Bits past the end of the buffer read as zero.
+fn read_bits(buf: &[u8], start: usize, len: usize) -> u32 {
+    (start..start + len).fold(0, |word, pos| {
+        let bit = buf.get(pos / 8).map_or(0, |&b| b >> (7 - pos % 8) & 1);
+        word << 1 | bit as u32
+    })
+}
+
+/// Write the given number of low bits of the word, MSB first, starting at the given
+/// bit offset, into a zeroed buffer.
+fn write_bits(buf: &mut [u8], start: usize, len: usize, word: u32) {
+    for i in 0..len {
+        let pos = start + i;
+        buf[pos / 8] |= ((word >> (len - 1 - i) & 1) as u8) << (7 - pos % 8);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use encode as encode_word;
+
+    #[test]
+    fn test_lengths() {
+        assert_eq!((codeword_count(0), encoded_len(0)), (0, 0));
+        assert_eq!((codeword_count(1), encoded_len(1)), (1, 3));
+        assert_eq!((codeword_count(9), encoded_len(9)), (8, 17));
+        assert_eq!((codeword_count(10), encoded_len(10)), (9, 20));
+    }
+
+    #[test]
+    fn test_layout() {
+        // 0xff 0x80 is exactly the single chunk 0x1ff, then an all-zero padded chunk.
+        let stream = encode(&[0xff, 0x80]);
+        let word = read_bits(&stream, 0, 17);
+
+        assert_eq!(word, encode_word(0x1ff));
+        assert_eq!(read_bits(&stream, 17, 17), 0);
+        assert_eq!(stream.len(), 5);
+        assert_eq!(stream[4] & 0b11, 0);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..40 {
+            let payload = (0..len).map(|i| (i * 37 + 11) as u8).collect::<Vec<_>>();
+            let stream = encode(&payload);
+
+            assert_eq!(decode(&stream, len), Ok((payload, 0)));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let payload = b"packed codewords";
+        let mut stream = encode(payload);
+
+        assert_eq!(decode(&stream[1..], payload.len()), Err(FrameError::Length));
+        assert_eq!(decode(&stream, payload.len() + 1), Err(FrameError::Length));
+
+        // One error in each of the first two codewords, then a burst in the fourth.
+        stream[0] ^= 0x80;
+        stream[3] ^= 0x01;
+        stream[6] ^= 0x1f;
+
+        assert_eq!(decode(&stream, payload.len()), Err(FrameError::Uncorrectable(3)));
+
+        stream[6] ^= 0x1f;
+        assert_eq!(decode(&stream, payload.len()), Ok((payload.to_vec(), 2)));
+    }
+}
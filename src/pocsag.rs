@@ -0,0 +1,203 @@
+//! Encoding and decoding of the (31, 21, 5) BCH code used by
+//! [POCSAG](https://en.wikipedia.org/wiki/POCSAG) paging.
+//!
+//! The generator polynomial for the code is given by
+//!
+//! > g(x) = x<sup>10</sup> + x<sup>9</sup> + x<sup>8</sup> + x<sup>6</sup> +
+//! > x<sup>5</sup> + x<sup>3</sup> + 1
+//!
+//! Each 32-bit POCSAG codeword holds the 21 data bits in the MSBs, followed by the 10
+//! parity bits and finally an even parity bit over the whole word in the LSB. The extra
+//! parity bit extends the code to (32, 21, 6), so it can correct up to 2 errors while
+//! still detecting 3.
+
+use code::{BlockCode, Encoder, Decoder};
+use {load, TableError};
+
+/// Encode the given 21 data bits into a 32-bit codeword.
+pub fn encode(data: u32) -> u32 {
+    assert_eq!(data >> 21, 0);
+
+    let word = data << 10 | remainder(data << 10);
+    word << 1 | word.count_ones() & 1
+}
+
+/// Try to decode the given 32-bit word to the nearest codeword, correcting up to 2
+/// errors.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 21 data
+/// bits and `err` is the number of corrected bits. Otherwise, return `None` to indicate
+/// an unrecoverable error.
+pub fn decode(word: u32) -> Option<(u32, usize)> {
+    let pat = match remainder(word >> 1) {
+        0 => 0,
+        s => match PATTERNS[s as usize] {
+            0 => return None,
+            pat => pat << 1,
+        },
+    };
+
+    let fixed = word ^ pat;
+    let err = pat.count_ones() as usize;
+
+    // An odd overall parity after correction means the parity bit itself is in error,
+    // which is only recoverable if the total doesn't go past 2 errors.
+    let err = match (fixed.count_ones() & 1, err) {
+        (0, err) => err,
+        (_, 2) => return None,
+        (_, err) => err + 1,
+    };
+
+    Some((fixed >> 11, err))
+}
+
+/// Verify the syndrome table against every correctable error pattern.
+///
+/// This is intended to be run once at startup to detect corrupted flash or a bad port.
+pub fn verify_tables() -> Result<(), TableError> {
+    if check_patterns(&PATTERNS) { Ok(()) } else { Err(TableError::Patterns) }
+}
+
+/// The POCSAG (32, 21, 6) code, for use with the generic [`code`](../code/index.html)
+/// traits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pocsag;
+
+impl Encoder for Pocsag {
+    type Data = u32;
+    type Codeword = u32;
+
+    fn encode(&self, data: u32) -> u32 { encode(data) }
+}
+
+impl Decoder for Pocsag {
+    fn decode(&self, word: u32) -> Option<(u32, usize)> { decode(word) }
+}
+
+impl BlockCode for Pocsag {
+    const N: usize = 32;
+    const K: usize = 21;
+    const D: usize = 6;
+}
+
+impl_dyn_block_code!(Pocsag, "pocsag32", u32, 21, u32, 32);
+
+/// Generator polynomial, with the x<sup>10</sup> term in the MSB.
+const GEN_POLY: u32 = 0b11101101001;
+
+/// Maps each 10-bit syndrome to an error pattern over the 31 BCH bits.
+///
+/// If a syndrome is invalid, the pattern is zero.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static PATTERNS: [u32; 1024] = build_patterns();
+
+/// Compute the remainder of the given 31-bit word divided by the generator polynomial.
+///
+/// This is both the parity for a shifted data word and the syndrome for a received word.
+const fn remainder(word: u32) -> u32 {
+    let mut rem = word;
+    let mut bit = 30;
+
+    while bit >= 10 {
+        if rem >> bit & 1 == 1 {
+            rem ^= GEN_POLY << (bit - 10);
+        }
+
+        bit -= 1;
+    }
+
+    rem
+}
+
+/// Build the syndrome table from every error pattern of weight 1 or 2.
+const fn build_patterns() -> [u32; 1024] {
+    let mut table = [0; 1024];
+    let mut i = 0;
+
+    while i < 31 {
+        table[remainder(1 << i) as usize] = 1 << i;
+
+        let mut j = i + 1;
+
+        while j < 31 {
+            let pat = 1 << i | 1 << j;
+            table[remainder(pat) as usize] = pat;
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+/// Check the given syndrome table against every error pattern of weight 1 or 2.
+fn check_patterns(patterns: &[u32]) -> bool {
+    let pats = (0..31).flat_map(|i| {
+        (i..31).map(move |j| 1 << i | 1 << j)
+    });
+
+    let valid = pats.clone().all(|pat| {
+        load(&patterns[remainder(pat) as usize]) == pat
+    });
+
+    // Every other entry must be empty.
+    valid && patterns.len() == 1024 &&
+        patterns.iter().filter(|&p| load(p) != 0).count() == pats.count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(0), 0);
+        assert_eq!(encode(0b111111111111111111111), 0xffffffff);
+
+        // Frame sync and idle codewords.
+        assert_eq!(encode(0x7cd215d8 >> 11), 0x7cd215d8);
+        assert_eq!(encode(0x7a89c197 >> 11), 0x7a89c197);
+    }
+
+    #[test]
+    fn test_decode() {
+        for d in (0..1 << 21).step_by(997) {
+            assert_eq!(decode(encode(d)), Some((d, 0)));
+        }
+
+        let w = encode(0x7a89c197 >> 11);
+
+        for i in 0..32 {
+            assert_eq!(decode(w ^ 1 << i), Some((0x7a89c197 >> 11, 1)));
+        }
+
+        for i in 0..32 {
+            for j in (i + 1)..32 {
+                assert_eq!(decode(w ^ 1 << i ^ 1 << j), Some((0x7a89c197 >> 11, 2)));
+            }
+        }
+
+        // Detect all three-bit errors.
+        for i in 0..32 {
+            for j in (i + 1)..32 {
+                for k in (j + 1)..32 {
+                    assert_eq!(decode(w ^ 1 << i ^ 1 << j ^ 1 << k), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_tables() {
+        assert_eq!(verify_tables(), Ok(()));
+
+        let mut pats = PATTERNS;
+        pats[remainder(1 << 7) as usize] = 1 << 8;
+        assert!(!check_patterns(&pats));
+
+        let mut pats = PATTERNS;
+        pats[pats.iter().position(|&p| p == 0).unwrap()] = 1;
+        assert!(!check_patterns(&pats));
+    }
+}
@@ -0,0 +1,377 @@
+//! Product codes built from any two block codes.
+//!
+//! A product code arranges its data bits in a matrix, encodes each row with one code,
+//! then encodes each column of the result with another. Every row and column of the
+//! full matrix is a codeword, so decoding alternates between row and column passes,
+//! with each pass repairing errors the other couldn't. This is the structure of DMR's
+//! block product turbo code, and [`ProductCode`](struct.ProductCode.html) builds one
+//! from any pair of the crate's block codes through the
+//! [`DynCode`](../code/trait.DynCode.html) interface. Padding bits outside the matrix
+//! and an interleaver over the whole word can be added, which is how the
+//! [`bptc`](../bptc/index.html) module is built.
+//!
+//! Both codes must be systematic, with the data bits first in transmission order, which
+//! is true of all the block codes in the crate.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::Cyclic;
+//! use cai_cyclic::product::ProductCode;
+//!
+//! let code = ProductCode::new(Cyclic, Cyclic);
+//! assert_eq!((code.data_len(), code.word_len()), (81, 289));
+//!
+//! let data = (0..81).map(|i| i % 5 == 0).collect::<Vec<_>>();
+//! let mut word = code.encode(&data);
+//!
+//! // Wipe out most of a row, far beyond what the row code can correct.
+//! for bit in &mut word[17..30] {
+//!     *bit = !*bit;
+//! }
+//!
+//! assert_eq!(code.decode(&word), Some((data, 13)));
+//! ```
+
+use code::DynCode;
+
+/// Product of a row code and a column code.
+#[derive(Clone, Debug)]
+pub struct ProductCode<R, C> {
+    /// Code applied to each row.
+    row: R,
+    /// Code applied to each column.
+    col: C,
+    /// Data and codeword lengths of the row code.
+    row_len: (usize, usize),
+    /// Data and codeword lengths of the column code.
+    col_len: (usize, usize),
+    /// Number of padding bits before the matrix.
+    padding: usize,
+    /// Transmitted position of each bit, padding first and then the matrix in row-major
+    /// order, if interleaved.
+    interleaver: Option<Vec<usize>>,
+    /// Maximum number of row and column pass pairs when decoding.
+    iterations: usize,
+}
+
+impl<R: DynCode, C: DynCode> ProductCode<R, C> {
+    /// Create a new product code with the given row and column codes, sent in row-major
+    /// order without padding or interleaving and decoded with up to 4 iterations.
+    pub fn new(row: R, col: C) -> Self {
+        let row_len = block_len(&row);
+        let col_len = block_len(&col);
+
+        ProductCode {
+            row,
+            col,
+            row_len,
+            col_len,
+            padding: 0,
+            interleaver: None,
+            iterations: 4,
+        }
+    }
+
+    /// Send the given number of padding bits before the matrix. They aren't covered by
+    /// either code, so they're sent as zero and ignored when decoding.
+    ///
+    /// This changes the codeword length, so it must be given before any interleaver.
+    pub fn with_padding(self, padding: usize) -> Self {
+        assert!(self.interleaver.is_none(), "padding must be given before the interleaver");
+        ProductCode { padding, ..self }
+    }
+
+    /// Send the codeword through the given interleaver, where `perm[i]` is the
+    /// transmitted position of the `i`th bit, counting the padding bits first and then
+    /// the matrix in row-major order.
+    pub fn with_interleaver(self, perm: Vec<usize>) -> Self {
+        let n = self.word_len();
+        assert_eq!(perm.len(), n);
+
+        let mut seen = vec![false; n];
+
+        for &p in &perm {
+            assert!(p < n && !seen[p], "interleaver must be a permutation");
+            seen[p] = true;
+        }
+
+        ProductCode { interleaver: Some(perm), ..self }
+    }
+
+    /// Change the maximum number of row and column pass pairs when decoding.
+    pub fn with_iterations(self, iterations: usize) -> Self {
+        assert!(iterations > 0);
+        ProductCode { iterations, ..self }
+    }
+
+    /// Number of data bits.
+    pub fn data_len(&self) -> usize { self.row_len.0 * self.col_len.0 }
+
+    /// Number of codeword bits.
+    pub fn word_len(&self) -> usize { self.padding + self.row_len.1 * self.col_len.1 }
+
+    /// Encode the given data bits, in row-major order.
+    pub fn encode(&self, data: &[bool]) -> Vec<bool> {
+        assert_eq!(data.len(), self.data_len());
+
+        let (k_row, n_row) = self.row_len;
+        let k_col = self.col_len.0;
+
+        let mut padded = vec![false; self.word_len()];
+        let matrix = &mut padded[self.padding..];
+
+        for (r, bits) in data.chunks(k_row).enumerate() {
+            let word = self.row.encode(bits).unwrap();
+            matrix[r * n_row..(r + 1) * n_row].copy_from_slice(&word);
+        }
+
+        for c in 0..n_row {
+            let bits = (0..k_col).map(|r| matrix[r * n_row + c]).collect::<Vec<_>>();
+
+            for (r, &bit) in self.col.encode(&bits).unwrap().iter().enumerate() {
+                matrix[r * n_row + c] = bit;
+            }
+        }
+
+        match self.interleaver {
+            Some(ref perm) => {
+                let mut word = vec![false; padded.len()];
+
+                for (&p, &bit) in perm.iter().zip(&padded) {
+                    word[p] = bit;
+                }
+
+                word
+            },
+            None => padded,
+        }
+    }
+
+    /// Try to decode the given received bits by alternating row and column passes until
+    /// every row and column is a codeword.
+    ///
+    /// If decoding converged, return `Some((data, err))`, where `err` is the total number
+    /// of corrected bits. Otherwise, return `None`.
+    pub fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        assert_eq!(word.len(), self.word_len());
+
+        let (k_row, n_row) = self.row_len;
+        let (k_col, n_col) = self.col_len;
+
+        let received = match self.interleaver {
+            Some(ref perm) => perm.iter().skip(self.padding).map(|&p| word[p]).collect(),
+            None => word[self.padding..].to_vec(),
+        };
+
+        let mut matrix = received.clone();
+
+        for _ in 0..self.iterations {
+            for r in 0..n_col {
+                repair(&self.row, &mut matrix[r * n_row..(r + 1) * n_row]);
+            }
+
+            let mut cols = true;
+
+            for c in 0..n_row {
+                let mut bits = (0..n_col).map(|r| matrix[r * n_row + c]).collect::<Vec<_>>();
+                cols &= repair(&self.col, &mut bits);
+
+                for (r, &bit) in bits.iter().enumerate() {
+                    matrix[r * n_row + c] = bit;
+                }
+            }
+
+            // Converged once the column pass leaves every row a codeword as well.
+            let rows = (0..n_col).all(|r| {
+                self.row.decode(&matrix[r * n_row..(r + 1) * n_row]).map(|(_, e)| e) == Some(0)
+            });
+
+            if cols && rows {
+                let data = (0..k_col)
+                    .flat_map(|r| matrix[r * n_row..r * n_row + k_row].to_vec())
+                    .collect();
+
+                let err = matrix.iter().zip(&received).filter(|&(a, b)| a != b).count();
+
+                return Some((data, err));
+            }
+        }
+
+        None
+    }
+}
+
+impl<R: DynCode, C: DynCode> DynCode for ProductCode<R, C> {
+    fn name(&self) -> &'static str { "product" }
+
+    fn data_len(&self) -> Option<usize> { Some(ProductCode::data_len(self)) }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+        if data.len() == ProductCode::data_len(self) {
+            Some(ProductCode::encode(self, data))
+        } else {
+            None
+        }
+    }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        if word.len() == self.word_len() {
+            ProductCode::decode(self, word)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the data and codeword lengths of the given block code.
+fn block_len<T: DynCode>(code: &T) -> (usize, usize) {
+    let k = code.data_len().expect("product code requires block codes");
+    (k, code.encode(&vec![false; k]).unwrap().len())
+}
+
+/// Decode the given codeword bits in place, returning whether decoding succeeded.
+fn repair<T: DynCode>(code: &T, bits: &mut [bool]) -> bool {
+    match code.decode(bits) {
+        Some((_, 0)) => true,
+        Some((data, _)) => {
+            bits.copy_from_slice(&code.encode(&data).unwrap());
+            true
+        },
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Cyclic;
+    use sim::{RandomSource, SplitMix64};
+
+    fn random_data(rng: &mut SplitMix64, len: usize) -> Vec<bool> {
+        (0..len).map(|_| rng.next_u64() & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_encode() {
+        let code = ProductCode::new(Cyclic, Cyclic);
+        let mut rng = SplitMix64::new(3);
+        let data = random_data(&mut rng, 81);
+        let word = code.encode(&data);
+
+        // Every row and column is a codeword.
+        for r in 0..17 {
+            assert_eq!(Cyclic.decode(&word[r * 17..(r + 1) * 17]).map(|(_, e)| e), Some(0));
+        }
+
+        for c in 0..17 {
+            let col = (0..17).map(|r| word[r * 17 + c]).collect::<Vec<_>>();
+            assert_eq!(Cyclic.decode(&col).map(|(_, e)| e), Some(0));
+        }
+
+        // Data is systematic in the top left.
+        for r in 0..9 {
+            assert_eq!(&word[r * 17..r * 17 + 9], &data[r * 9..(r + 1) * 9]);
+        }
+
+        assert_eq!(code.decode(&word), Some((data, 0)));
+    }
+
+    #[test]
+    fn test_decode() {
+        let code = ProductCode::new(Cyclic, Cyclic);
+        let mut rng = SplitMix64::new(11);
+
+        for _ in 0..50 {
+            let data = random_data(&mut rng, 81);
+            let mut word = code.encode(&data);
+
+            // Scattered errors, one per row and column.
+            for i in 0..17 {
+                word[i * 17 + i] ^= true;
+            }
+
+            // Bursts in two rows, beyond the row code.
+            for c in 0..5 {
+                word[3 * 17 + c] ^= true;
+                word[10 * 17 + 6 + c] ^= true;
+            }
+
+            let (decoded, err) = code.decode(&word).unwrap();
+            assert_eq!(decoded, data);
+            assert_eq!(err, 23);
+        }
+
+        // Too many errors to converge.
+        let mut word = code.encode(&[false; 81]);
+
+        for bit in word.iter_mut().step_by(3) {
+            *bit = !*bit;
+        }
+
+        assert_ne!(code.decode(&word).map(|(d, _)| d), Some(vec![false; 81]));
+    }
+
+    #[test]
+    fn test_interleaver() {
+        // Transpose on the way out, so a burst lands in a column instead of a row.
+        let perm = (0..289).map(|i| i % 17 * 17 + i / 17).collect();
+        let code = ProductCode::new(Cyclic, Cyclic).with_interleaver(perm).with_iterations(1);
+
+        let data = (0..81).map(|i| i % 2 == 0).collect::<Vec<_>>();
+        let mut word = code.encode(&data);
+
+        for bit in &mut word[0..17] {
+            *bit = !*bit;
+        }
+
+        assert_eq!(code.decode(&word), Some((data, 17)));
+    }
+
+    #[test]
+    fn test_padding() {
+        let perm = (0..291).rev().collect();
+        let code = ProductCode::new(Cyclic, Cyclic).with_padding(2).with_interleaver(perm);
+        assert_eq!(code.word_len(), 291);
+
+        let data = (0..81).map(|i| i % 4 == 1).collect::<Vec<_>>();
+        let mut word = code.encode(&data);
+
+        // The padding bits are sent last by the reversing interleaver.
+        assert!(!word[289] && !word[290]);
+
+        word[290] = true;
+        word[5] ^= true;
+        assert_eq!(code.decode(&word), Some((data, 1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_padding_order() {
+        let perm = (0..289).collect();
+        ProductCode::new(Cyclic, Cyclic).with_interleaver(perm).with_padding(1);
+    }
+
+    #[test]
+    fn test_dyn() {
+        #[cfg(feature = "pocsag")]
+        {
+            let code: Box<dyn DynCode> = Box::new(ProductCode::new(Cyclic, ::pocsag::Pocsag));
+            let data = vec![true; 9 * 21];
+            let word = code.encode(&data).unwrap();
+
+            assert_eq!(word.len(), 17 * 32);
+            assert_eq!(code.decode(&word), Some((data, 0)));
+        }
+
+        let code = ProductCode::new(Cyclic, Cyclic);
+        assert_eq!(DynCode::encode(&code, &[true; 80]), None);
+        assert_eq!(DynCode::decode(&code, &[true; 290]), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bad_interleaver() {
+        ProductCode::new(Cyclic, Cyclic).with_interleaver(vec![0; 289]);
+    }
+}
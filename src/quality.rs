@@ -0,0 +1,121 @@
+//! Combining the FEC outcomes of a burst into a single quality score.
+//!
+//! A burst usually carries several separately protected fields, such as a sync pattern,
+//! an EMB or slot type word, and a payload. Each field's outcome is scored by how much of
+//! its correction capacity was used, and the scores are combined by weight. The result
+//! is suitable for squelch decisions and signal quality displays.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::quality::BurstQuality;
+//!
+//! let mut q = BurstQuality::new();
+//!
+//! // Sync pattern with 1 of at most 4 tolerated bit errors.
+//! q.add(Some(1), 4, 1);
+//! // EMB decoded cleanly.
+//! q.add_decoded(cai_cyclic::decode(cai_cyclic::encode(0b1010)), 2, 1);
+//! // Payload failed to decode.
+//! q.add(None, 2, 2);
+//!
+//! assert_eq!(q.fields(), 3);
+//! assert_eq!(q.failures(), 1);
+//! assert!((q.score() - 0.45).abs() < 1e-6);
+//! ```
+
+/// Accumulates per-field decode outcomes within a burst.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BurstQuality {
+    /// Weighted sum of field scores.
+    total: f32,
+    /// Sum of field weights.
+    weights: u32,
+    /// Number of fields recorded.
+    fields: usize,
+    /// Number of fields that failed to decode.
+    failures: usize,
+}
+
+impl BurstQuality {
+    /// Create a new, empty accumulator.
+    pub fn new() -> Self { Self::default() }
+
+    /// Record a field outcome, where `errors` is the number of corrected bits or `None`
+    /// for an unrecoverable field, `capacity` is the most errors the field's code can
+    /// correct (or the most tolerated for a sync pattern), and `weight` is the field's
+    /// relative importance.
+    ///
+    /// A clean field scores 1, a field at full capacity scores 1 / (capacity + 1), and a
+    /// failed field scores 0.
+    pub fn add(&mut self, errors: Option<usize>, capacity: usize, weight: u32) {
+        let score = match errors {
+            Some(err) => {
+                assert!(err <= capacity);
+                1.0 - err as f32 / (capacity + 1) as f32
+            },
+            None => {
+                self.failures += 1;
+                0.0
+            },
+        };
+
+        self.total += score * weight as f32;
+        self.weights += weight;
+        self.fields += 1;
+    }
+
+    /// Record the result of a decoder returning `Option<(data, err)>`, as in `add()`.
+    pub fn add_decoded<T>(&mut self, result: Option<(T, usize)>, capacity: usize,
+                          weight: u32)
+    {
+        self.add(result.map(|(_, err)| err), capacity, weight)
+    }
+
+    /// Number of fields recorded.
+    pub fn fields(&self) -> usize { self.fields }
+
+    /// Number of recorded fields that failed to decode.
+    pub fn failures(&self) -> usize { self.failures }
+
+    /// Weighted quality score from 0 (all fields failed) to 1 (all fields clean).
+    ///
+    /// An empty accumulator scores 0.
+    pub fn score(&self) -> f32 {
+        if self.weights == 0 { 0.0 } else { self.total / self.weights as f32 }
+    }
+
+    /// Clear all recorded fields.
+    pub fn reset(&mut self) { *self = Self::default() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quality() {
+        let mut q = BurstQuality::new();
+        assert_eq!(q.score(), 0.0);
+
+        q.add(Some(0), 2, 1);
+        assert_eq!(q.score(), 1.0);
+
+        q.add(Some(2), 2, 1);
+        assert!((q.score() - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(q.failures(), 0);
+
+        q.add_decoded::<u16>(None, 2, 2);
+        assert!((q.score() - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(q.fields(), 3);
+        assert_eq!(q.failures(), 1);
+
+        // Zero-weight fields are counted but don't change the score.
+        q.add(None, 2, 0);
+        assert!((q.score() - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(q.failures(), 2);
+
+        q.reset();
+        assert_eq!(q, BurstQuality::new());
+    }
+}
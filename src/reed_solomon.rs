@@ -0,0 +1,699 @@
+//! Encoding and decoding of the shortened Reed-Solomon codes used by P25 and DMR.
+//!
+//! P25 protects its link control words with Reed-Solomon codes over GF(2<sup>6</sup>),
+//! generated by the primitive polynomial
+//!
+//! > p(x) = x<sup>6</sup> + x + 1
+//!
+//! Each symbol is a 6-bit "hexbit", stored in the low bits of a `u8`. Codewords hold
+//! the data symbols first, in transmission order, followed by the parity symbols, with
+//! the first symbol as the highest-degree coefficient of the codeword polynomial. The
+//! codes are shortened from the full length of 63 symbols by deleting leading data
+//! symbols, and a code with `n - k` parity symbols has the generator polynomial with
+//! roots α<sup>1</sup> through α<sup>n-k</sup>.
+//!
+//! Three codes are provided, all with the same interface:
+//!
+//! - (24, 12, 13), protecting link control in LDU1 and TDULC frames.
+//! - (24, 16, 9), protecting the encryption sync word in LDU2 frames.
+//! - (36, 20, 17), protecting the header data unit.
+//!
+//! Each code, and the DMR code below, is also available through the generic
+//! [`code`](../code/index.html) traits, as [`Rs24_12`](struct.Rs24_12.html) and so on.
+//!
+//! ## DMR full link control
+//!
+//! DMR protects the 9-byte full link control word with a (12, 9, 4) code over
+//! GF(2<sup>8</sup>), generated by the primitive polynomial
+//!
+//! > p(x) = x<sup>8</sup> + x<sup>4</sup> + x<sup>3</sup> + x<sup>2</sup> + 1
+//!
+//! with one byte per symbol and the same layout and generator roots as the P25 codes.
+//! The 3 parity bytes are XORed with a mask that depends on the burst's data type, so
+//! an LC word can't be accepted from the wrong kind of burst. This is handled by
+//! [`encode_full_lc`](fn.encode_full_lc.html) and
+//! [`decode_full_lc`](fn.decode_full_lc.html), with the unmasked code available as
+//! [`encode_12_9`](fn.encode_12_9.html) and [`decode_12_9`](fn.decode_12_9.html).
+//!
+//! ## Decoding
+//!
+//! Decoding corrects any combination of `e` symbol errors and `f` erasures, which are
+//! symbols known to be unreliable, as long as `2e + f` doesn't exceed the number of
+//! parity symbols.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::reed_solomon;
+//!
+//! let data = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+//! let mut word = reed_solomon::encode_24_12(&data);
+//!
+//! // Six symbol errors, the most the code can correct alone.
+//! for i in 0..6 {
+//!     word[i * 4] ^= 0x2a;
+//! }
+//!
+//! assert_eq!(reed_solomon::decode_24_12(&word, &[]), Some((data, 6)));
+//!
+//! // Marking some of them as erasures leaves room for more errors.
+//! word[1] ^= 0x15;
+//! assert_eq!(reed_solomon::decode_24_12(&word, &[0, 4, 8]), Some((data, 7)));
+//! ```
+//!
+//! ```rust
+//! use cai_cyclic::reed_solomon::{self, LcMask};
+//!
+//! let lc = [0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x56, 0x78];
+//! let mut bytes = reed_solomon::encode_full_lc(&lc, LcMask::VoiceHeader);
+//!
+//! bytes[5] ^= 0xff;
+//! assert_eq!(reed_solomon::decode_full_lc(&bytes, LcMask::VoiceHeader), Some((lc, 1)));
+//! assert_eq!(reed_solomon::decode_full_lc(&bytes, LcMask::Terminator), None);
+//! ```
+
+use code::{Decoder, DynCode, Encoder, from_symbols, to_symbols};
+use gf::{Field, GF64};
+
+/// Encode the given 12 data symbols into a 24-symbol (24, 12, 13) codeword.
+pub fn encode_24_12(data: &[u8; 12]) -> [u8; 24] { encode_array(&RS_24_12, data) }
+
+/// Try to decode the given 24-symbol (24, 12, 13) word, correcting up to 6 symbol
+/// errors, with each erasure at the given positions taking the place of half an error.
+///
+/// If decoding was successful, return `Some((data, err))`, where `data` is the 12 data
+/// symbols and `err` is the number of corrected symbols, including erased symbols that
+/// were in error. Otherwise, return `None` to indicate an unrecoverable error.
+pub fn decode_24_12(word: &[u8; 24], erasures: &[usize]) -> Option<([u8; 12], usize)> {
+    decode_array(&RS_24_12, word, erasures)
+}
+
+/// Encode the given 16 data symbols into a 24-symbol (24, 16, 9) codeword.
+pub fn encode_24_16(data: &[u8; 16]) -> [u8; 24] { encode_array(&RS_24_16, data) }
+
+/// Try to decode the given 24-symbol (24, 16, 9) word, correcting up to 4 symbol
+/// errors, with results as for [`decode_24_12`](fn.decode_24_12.html).
+pub fn decode_24_16(word: &[u8; 24], erasures: &[usize]) -> Option<([u8; 16], usize)> {
+    decode_array(&RS_24_16, word, erasures)
+}
+
+/// Encode the given 20 data symbols into a 36-symbol (36, 20, 17) codeword.
+pub fn encode_36_20(data: &[u8; 20]) -> [u8; 36] { encode_array(&RS_36_20, data) }
+
+/// Try to decode the given 36-symbol (36, 20, 17) word, correcting up to 8 symbol
+/// errors, with results as for [`decode_24_12`](fn.decode_24_12.html).
+pub fn decode_36_20(word: &[u8; 36], erasures: &[usize]) -> Option<([u8; 20], usize)> {
+    decode_array(&RS_36_20, word, erasures)
+}
+
+/// Encode the given 9 data bytes into a 12-byte (12, 9, 4) codeword, without a mask.
+pub fn encode_12_9(data: &[u8; 9]) -> [u8; 12] { encode_array(&RS_12_9, data) }
+
+/// Try to decode the given 12-byte (12, 9, 4) word, without a mask, correcting up to 1
+/// byte error, with results as for [`decode_24_12`](fn.decode_24_12.html).
+pub fn decode_12_9(word: &[u8; 12], erasures: &[usize]) -> Option<([u8; 9], usize)> {
+    decode_array(&RS_12_9, word, erasures)
+}
+
+/// Implement the generic code traits for a code with the given encoding and decoding
+/// functions, data and codeword lengths in symbols, and symbol width in bits.
+macro_rules! impl_rs_code {
+    ($code:ident, $name:expr, $encode:ident, $decode:ident, $k:expr, $n:expr, $width:expr) => {
+        impl Encoder for $code {
+            type Data = [u8; $k];
+            type Codeword = [u8; $n];
+
+            fn encode(&self, data: [u8; $k]) -> [u8; $n] { $encode(&data) }
+        }
+
+        impl Decoder for $code {
+            fn decode(&self, word: [u8; $n]) -> Option<([u8; $k], usize)> {
+                $decode(&word, &[])
+            }
+        }
+
+        impl DynCode for $code {
+            fn name(&self) -> &'static str { $name }
+
+            fn data_len(&self) -> Option<usize> { Some($k * $width) }
+
+            fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+                if data.len() != $k * $width {
+                    return None;
+                }
+
+                let mut syms = [0; $k];
+                to_symbols(data, $width, &mut syms);
+
+                Some(from_symbols(&$encode(&syms), $width))
+            }
+
+            fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+                if word.len() != $n * $width {
+                    return None;
+                }
+
+                let mut syms = [0; $n];
+                to_symbols(word, $width, &mut syms);
+
+                $decode(&syms, &[]).map(|(data, err)| (from_symbols(&data, $width), err))
+            }
+        }
+    };
+}
+
+/// The (24, 12, 13) code, for use with the generic [`code`](../code/index.html) traits.
+///
+/// Words are symbol arrays, or 6 bits per symbol through `DynCode`, and errors are
+/// counted in symbols rather than bits. The traits decode without erasures.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rs24_12;
+
+impl_rs_code!(Rs24_12, "rs24_12", encode_24_12, decode_24_12, 12, 24, 6);
+
+/// The (24, 16, 9) code, for use with the generic [`code`](../code/index.html) traits,
+/// as for [`Rs24_12`](struct.Rs24_12.html).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rs24_16;
+
+impl_rs_code!(Rs24_16, "rs24_16", encode_24_16, decode_24_16, 16, 24, 6);
+
+/// The (36, 20, 17) code, for use with the generic [`code`](../code/index.html) traits,
+/// as for [`Rs24_12`](struct.Rs24_12.html).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rs36_20;
+
+impl_rs_code!(Rs36_20, "rs36_20", encode_36_20, decode_36_20, 20, 36, 6);
+
+/// The unmasked DMR (12, 9, 4) code, for use with the generic
+/// [`code`](../code/index.html) traits, as for [`Rs24_12`](struct.Rs24_12.html) but
+/// with 8 bits per symbol.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rs12_9;
+
+impl_rs_code!(Rs12_9, "rs12_9", encode_12_9, decode_12_9, 9, 12, 8);
+
+/// Parity mask applied to a DMR full link control word.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LcMask {
+    /// Voice LC header burst.
+    VoiceHeader,
+    /// Terminator with LC burst.
+    Terminator,
+}
+
+impl LcMask {
+    /// Byte XORed into each of the 3 parity bytes.
+    pub fn value(self) -> u8 {
+        match self {
+            LcMask::VoiceHeader => 0x96,
+            LcMask::Terminator => 0x99,
+        }
+    }
+}
+
+/// Encode the given 9-byte full link control word into its 12 transmitted bytes, with
+/// the parity masked for the given burst type.
+pub fn encode_full_lc(lc: &[u8; 9], mask: LcMask) -> [u8; 12] {
+    let mut bytes = encode_12_9(lc);
+    apply_mask(&mut bytes, mask);
+    bytes
+}
+
+/// Try to decode the given 12 received full link control bytes, with the parity masked
+/// for the given burst type, correcting up to 1 byte error.
+///
+/// If decoding was successful, return `Some((lc, err))`, where `lc` is the 9-byte link
+/// control word and `err` is the number of corrected bytes. Otherwise, return `None` to
+/// indicate an unrecoverable error or the wrong mask.
+pub fn decode_full_lc(bytes: &[u8; 12], mask: LcMask) -> Option<([u8; 9], usize)> {
+    let mut word = *bytes;
+    apply_mask(&mut word, mask);
+    decode_12_9(&word, &[])
+}
+
+/// XOR the given mask into the parity bytes of a full link control word.
+fn apply_mask(bytes: &mut [u8; 12], mask: LcMask) {
+    for b in &mut bytes[9..] {
+        *b ^= mask.value();
+    }
+}
+
+/// Encode the given data symbols with the given code.
+fn encode_array<const K: usize, const N: usize>(code: &ReedSolomon, data: &[u8; K])
+    -> [u8; N]
+{
+    let mut word = [0; N];
+    code.encode(data, &mut word);
+    word
+}
+
+/// Decode the given word with the given code.
+fn decode_array<const N: usize, const K: usize>(code: &ReedSolomon, word: &[u8; N],
+                                                erasures: &[usize])
+    -> Option<([u8; K], usize)>
+{
+    let mut word = *word;
+    let err = code.decode(&mut word, erasures)?;
+
+    let mut data = [0; K];
+    data.copy_from_slice(&word[..K]);
+
+    Some((data, err))
+}
+
+/// GF(2<sup>8</sup>) with primitive polynomial x<sup>8</sup> + x<sup>4</sup> + x<sup>3</sup>
+/// + x<sup>2</sup> + 1.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static GF256: Field = Field::new(8, 0b100011101);
+
+/// The (24, 12, 13) code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RS_24_12: ReedSolomon = ReedSolomon::new(&GF64, 24, 12);
+
+/// The (24, 16, 9) code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RS_24_16: ReedSolomon = ReedSolomon::new(&GF64, 24, 16);
+
+/// The (36, 20, 17) code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RS_36_20: ReedSolomon = ReedSolomon::new(&GF64, 36, 20);
+
+/// The DMR (12, 9, 4) code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RS_12_9: ReedSolomon = ReedSolomon::new(&GF256, 12, 9);
+
+/// Maximum number of parity symbols of any code.
+const MAX_PARITY: usize = 16;
+
+/// Shortened systematic Reed-Solomon code.
+struct ReedSolomon {
+    /// Symbol field.
+    field: &'static Field,
+    /// Number of symbols in a codeword.
+    n: usize,
+    /// Number of data symbols in a codeword.
+    k: usize,
+    /// Coefficients of the monic generator polynomial, from the lowest degree.
+    gen: [u8; MAX_PARITY + 1],
+}
+
+impl ReedSolomon {
+    /// Create a new code with the given codeword and data lengths, with the generator
+    /// polynomial having roots α<sup>1</sup> through α<sup>n-k</sup>.
+    const fn new(field: &'static Field, n: usize, k: usize) -> Self {
+        assert!(k < n && n <= field.order && n - k <= MAX_PARITY);
+
+        let mut gen = [0; MAX_PARITY + 1];
+        gen[0] = 1;
+
+        // Multiply in each factor (x - α^i) in turn.
+        let mut i = 1;
+
+        while i <= n - k {
+            let root = field.exp[i];
+            let mut j = i;
+
+            while j > 0 {
+                gen[j] = gen[j - 1] ^ field.mul(gen[j], root);
+                j -= 1;
+            }
+
+            gen[0] = field.mul(gen[0], root);
+            i += 1;
+        }
+
+        ReedSolomon { field, n, k, gen }
+    }
+
+    /// Number of parity symbols.
+    fn parity(&self) -> usize { self.n - self.k }
+
+    /// Encode the given data symbols into the given codeword buffer.
+    fn encode(&self, data: &[u8], word: &mut [u8]) {
+        assert_eq!(data.len(), self.k);
+        assert_eq!(word.len(), self.n);
+        assert!(data.iter().all(|&d| d as usize <= self.field.order));
+
+        let np = self.parity();
+
+        // Divide data(x) * x^(n - k) by the generator polynomial, with the remainder
+        // held from the highest degree.
+        let mut rem = [0; MAX_PARITY];
+
+        for &d in data {
+            let fb = d ^ rem[0];
+
+            for j in 0..np - 1 {
+                rem[j] = rem[j + 1] ^ self.field.mul(fb, self.gen[np - 1 - j]);
+            }
+
+            rem[np - 1] = self.field.mul(fb, self.gen[0]);
+        }
+
+        word[..self.k].copy_from_slice(data);
+        word[self.k..].copy_from_slice(&rem[..np]);
+    }
+
+    /// Compute the syndromes of the given word, returning whether any is nonzero.
+    fn syndromes(&self, word: &[u8], syn: &mut [u8; MAX_PARITY]) -> bool {
+        for (j, s) in syn.iter_mut().take(self.parity()).enumerate() {
+            let x = self.field.alpha(j + 1);
+            *s = word.iter().fold(0, |acc, &c| self.field.mul(acc, x) ^ c);
+        }
+
+        syn.iter().any(|&s| s != 0)
+    }
+
+    /// Try to correct the given word in place, with erasures at the given positions,
+    /// returning the number of corrected symbols.
+    fn decode(&self, word: &mut [u8], erasures: &[usize]) -> Option<usize> {
+        assert_eq!(word.len(), self.n);
+        assert!(word.iter().all(|&c| c as usize <= self.field.order));
+        assert!(erasures.iter().all(|&e| e < self.n));
+
+        let gf = self.field;
+        let np = self.parity();
+        let mut syn = [0; MAX_PARITY];
+
+        if !self.syndromes(word, &mut syn) {
+            return Some(0);
+        }
+
+        if erasures.len() > np {
+            return None;
+        }
+
+        // Symbol i is the coefficient of x^(n - 1 - i), so its locator is α^(n - 1 - i).
+        let locator = |pos: usize| gf.alpha(self.n - 1 - pos);
+
+        // Start the error locator from the erasure locator, the product of (1 - X x)
+        // over the erasure locators X.
+        let mut lambda = [0; MAX_PARITY + 1];
+        lambda[0] = 1;
+
+        for (i, &pos) in erasures.iter().enumerate() {
+            let x = locator(pos);
+
+            for j in (1..i + 2).rev() {
+                lambda[j] ^= gf.mul(lambda[j - 1], x);
+            }
+        }
+
+        // Berlekamp-Massey, continuing from the erasures.
+        let f = erasures.len();
+        let mut b = lambda;
+        let mut len = f;
+
+        for r in f + 1..np + 1 {
+            let discr = (0..r).fold(0, |acc, i| acc ^ gf.mul(lambda[i], syn[r - 1 - i]));
+
+            if discr == 0 {
+                shift(&mut b);
+                continue;
+            }
+
+            let mut next = lambda;
+
+            for i in 1..MAX_PARITY + 1 {
+                next[i] ^= gf.mul(discr, b[i - 1]);
+            }
+
+            if 2 * len < r + f {
+                len = r + f - len;
+
+                for (bi, &li) in b.iter_mut().zip(&lambda) {
+                    *bi = gf.div(li, discr);
+                }
+            } else {
+                shift(&mut b);
+            }
+
+            lambda = next;
+        }
+
+        let deg = lambda.iter().rposition(|&c| c != 0).unwrap();
+
+        if deg < f || 2 * (deg - f) + f > np {
+            return None;
+        }
+
+        // Error evaluator Ω(x) = S(x) Λ(x) mod x^(n - k).
+        let mut omega = [0; MAX_PARITY];
+
+        for (i, o) in omega.iter_mut().take(np).enumerate() {
+            *o = (0..i + 1).fold(0, |acc, j| acc ^ gf.mul(lambda[j], syn[i - j]));
+        }
+
+        // Formal derivative, which keeps only the odd-degree terms in characteristic 2.
+        let mut deriv = [0; MAX_PARITY];
+
+        for i in (1..deg + 1).step_by(2) {
+            deriv[i - 1] = lambda[i];
+        }
+
+        // Chien search over the positions actually sent, applying the Forney algorithm
+        // at each root.
+        let mut found = 0;
+        let mut fixed = 0;
+
+        for (pos, sym) in word.iter_mut().enumerate() {
+            let xinv = gf.div(1, locator(pos));
+
+            if gf.eval(&lambda[..deg + 1], xinv) != 0 {
+                continue;
+            }
+
+            found += 1;
+
+            // A repeated root also means there were too many errors.
+            let slope = gf.eval(&deriv[..deg], xinv);
+
+            if slope == 0 {
+                return None;
+            }
+
+            let err = gf.div(gf.eval(&omega[..np], xinv), slope);
+
+            if err != 0 {
+                *sym ^= err;
+                fixed += 1;
+            }
+        }
+
+        // Roots in the deleted positions, or a locator that doesn't split, mean there
+        // were too many errors.
+        if found != deg || self.syndromes(word, &mut syn) {
+            return None;
+        }
+
+        Some(fixed)
+    }
+}
+
+/// Multiply the given polynomial by x.
+fn shift(poly: &mut [u8; MAX_PARITY + 1]) {
+    for i in (1..poly.len()).rev() {
+        poly[i] = poly[i - 1];
+    }
+
+    poly[0] = 0;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sim::{RandomSource, SplitMix64};
+
+    /// Fill the given buffer with random symbols of the given field.
+    fn random_symbols(rng: &mut SplitMix64, field: &Field, out: &mut [u8]) {
+        for s in out.iter_mut() {
+            *s = (rng.next_u64() % (field.order as u64 + 1)) as u8;
+        }
+    }
+
+    /// Pick a random nonzero symbol of the given field.
+    fn random_error(rng: &mut SplitMix64, field: &Field) -> u8 {
+        (rng.next_u64() % field.order as u64 + 1) as u8
+    }
+
+    /// Pick the given number of distinct positions below `n`.
+    fn positions(rng: &mut SplitMix64, n: usize, count: usize) -> Vec<usize> {
+        let mut pos = Vec::new();
+
+        while pos.len() < count {
+            let p = (rng.next_u64() % n as u64) as usize;
+
+            if !pos.contains(&p) {
+                pos.push(p);
+            }
+        }
+
+        pos
+    }
+
+    /// Every code, for tests that apply to all of them.
+    static CODES: [&ReedSolomon; 4] = [&RS_24_12, &RS_24_16, &RS_36_20, &RS_12_9];
+
+    /// Encode random data with the given code.
+    fn random_word(rng: &mut SplitMix64, code: &ReedSolomon) -> Vec<u8> {
+        let mut data = vec![0; code.k];
+        random_symbols(rng, code.field, &mut data);
+
+        let mut word = vec![0; code.n];
+        code.encode(&data, &mut word);
+        word
+    }
+
+    #[test]
+    fn test_generator() {
+        for code in &CODES {
+            let np = code.parity();
+            let g = &code.gen[..np + 1];
+            assert_eq!(g[np], 1);
+
+            for i in 1..np + 1 {
+                assert_eq!(code.field.eval(g, code.field.alpha(i)), 0);
+            }
+
+            assert_ne!(code.field.eval(g, code.field.alpha(np + 1)), 0);
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode_24_12(&[0; 12]), [0; 24]);
+        assert_eq!(encode_24_16(&[0; 16]), [0; 24]);
+        assert_eq!(encode_36_20(&[0; 20]), [0; 36]);
+
+        let mut rng = SplitMix64::new(17);
+
+        for code in &CODES {
+            for _ in 0..200 {
+                let mut word = random_word(&mut rng, code);
+                let mut syn = [0; MAX_PARITY];
+                let orig = word.clone();
+
+                assert!(!code.syndromes(&word, &mut syn));
+                assert_eq!(code.decode(&mut word, &[]), Some(0));
+                assert_eq!(word, orig);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrappers() {
+        let data = [0x3f; 20];
+        let word = encode_36_20(&data);
+        assert_eq!(&word[..20], &data);
+        assert_eq!(decode_36_20(&word, &[]), Some((data, 0)));
+
+        let data = [0x15; 16];
+        let mut word = encode_24_16(&data);
+        word[23] ^= 1;
+        assert_eq!(&word[..16], &data);
+        assert_eq!(decode_24_16(&word, &[]), Some((data, 1)));
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut rng = SplitMix64::new(23);
+
+        // Every mix of errors and erasures within the correction capability.
+        for code in &CODES {
+            let np = code.parity();
+
+            for f in 0..np + 1 {
+                for e in 0..(np - f) / 2 + 1 {
+                    for _ in 0..10 {
+                        let orig = random_word(&mut rng, code);
+                        let mut word = orig.clone();
+                        let pos = positions(&mut rng, code.n, e + f);
+
+                        for &p in &pos {
+                            word[p] ^= random_error(&mut rng, code.field);
+                        }
+
+                        assert_eq!(code.decode(&mut word, &pos[e..]), Some(e + f));
+                        assert_eq!(word, orig);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_lc() {
+        assert_eq!(GF256.alpha(8), 0b00011101);
+        assert_eq!(&RS_12_9.gen[..4], &[64, 56, 14, 1]);
+
+        let lc = [0x03, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x01, 0x02];
+        let plain = encode_12_9(&lc);
+
+        for &mask in &[LcMask::VoiceHeader, LcMask::Terminator] {
+            let bytes = encode_full_lc(&lc, mask);
+            assert_eq!(&bytes[..9], &lc);
+
+            for i in 9..12 {
+                assert_eq!(bytes[i], plain[i] ^ mask.value());
+            }
+
+            assert_eq!(decode_full_lc(&bytes, mask), Some((lc, 0)));
+
+            for i in 0..12 {
+                let mut bad = bytes;
+                bad[i] ^= 0x5a;
+                assert_eq!(decode_full_lc(&bad, mask), Some((lc, 1)));
+
+                bad[(i + 5) % 12] ^= 0x81;
+                assert_ne!(decode_full_lc(&bad, mask), Some((lc, 2)));
+            }
+        }
+
+        // The masks differ in every parity byte, beyond the correction capability.
+        let bytes = encode_full_lc(&lc, LcMask::Terminator);
+        assert_eq!(decode_full_lc(&bytes, LcMask::VoiceHeader), None);
+        assert_eq!(decode_12_9(&plain, &[]), Some((lc, 0)));
+    }
+
+    #[test]
+    fn test_erasure_unchanged() {
+        let data = [63; 12];
+        let mut word = encode_24_12(&data);
+        word[20] ^= 1;
+
+        // Erasing a correct symbol costs capacity but isn't counted as a correction.
+        assert_eq!(decode_24_12(&word, &[3, 20]), Some((data, 1)));
+    }
+
+    #[test]
+    fn test_failure() {
+        let mut rng = SplitMix64::new(29);
+
+        for code in &CODES {
+            let mut failed = 0;
+
+            for _ in 0..200 {
+                let orig = random_word(&mut rng, code);
+                let mut word = orig.clone();
+
+                for p in positions(&mut rng, code.n, code.parity() / 2 + 1) {
+                    word[p] ^= random_error(&mut rng, code.field);
+                }
+
+                match code.decode(&mut word, &[]) {
+                    Some(_) => assert_ne!(word, orig),
+                    None => failed += 1,
+                }
+            }
+
+            // Miscorrection beyond the capability is rare.
+            assert!(failed > 180);
+        }
+
+        // Too many erasures.
+        let mut word = [0; 24];
+        word[0] = 1;
+        assert_eq!(decode_24_12(&word, &(0..13).collect::<Vec<_>>()), None);
+    }
+}
@@ -0,0 +1,119 @@
+//! Brute-force reference decoder and differential testing harness.
+//!
+//! The reference decoder makes no use of the code structure: it compares the received
+//! word against every codeword and picks the nearest. It's slow, but simple enough to be
+//! obviously correct, so optimized decoders can be validated against it mechanically.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::reference;
+//! use cai_cyclic::sim::SplitMix64;
+//!
+//! assert_eq!(reference::check_random(cai_cyclic::decode, SplitMix64::new(1), 1000), Ok(()));
+//! ```
+
+use sim::{ErrorGenerator, RandomSource};
+use codewords;
+
+/// Try to decode the given 17-bit word by exhaustive search for the nearest codeword,
+/// correcting up to 2 errors.
+///
+/// This has the same interface and results as [`decode`](../fn.decode.html).
+pub fn decode(word: u32) -> Option<(u16, usize)> {
+    assert_eq!(word >> 17, 0);
+
+    let (data, dist) = codewords().enumerate()
+        .map(|(data, w)| (data as u16, (w ^ word).count_ones() as usize))
+        .min_by_key(|&(_, dist)| dist)
+        .unwrap();
+
+    // Within this radius the nearest codeword is always unique.
+    if dist <= 2 { Some((data, dist)) } else { None }
+}
+
+/// A word where a decoder under test disagrees with the reference decoder.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Received word given to both decoders.
+    pub word: u32,
+    /// Result from the reference decoder.
+    pub expected: Option<(u16, usize)>,
+    /// Result from the decoder under test.
+    pub actual: Option<(u16, usize)>,
+}
+
+/// Compare the given decoder against the reference decoder for each of the given words,
+/// stopping at the first disagreement.
+pub fn check_words<F, I>(mut decoder: F, words: I) -> Result<(), Mismatch> where
+    F: FnMut(u32) -> Option<(u16, usize)>,
+    I: IntoIterator<Item = u32>,
+{
+    for word in words {
+        let expected = decode(word);
+        let actual = decoder(word);
+
+        if expected != actual {
+            return Err(Mismatch { word, expected, actual });
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the given decoder against the reference decoder for every possible 17-bit
+/// word.
+pub fn check_exhaustive<F>(decoder: F) -> Result<(), Mismatch> where
+    F: FnMut(u32) -> Option<(u16, usize)>
+{
+    check_words(decoder, 0..1 << 17)
+}
+
+/// Compare the given decoder against the reference decoder for the given number of
+/// random codewords, each corrupted by 0 to 4 random bit errors.
+pub fn check_random<F, R>(decoder: F, mut rng: R, count: usize) -> Result<(), Mismatch> where
+    F: FnMut(u32) -> Option<(u16, usize)>,
+    R: RandomSource,
+{
+    let words = (0..count).map(|_| {
+        let data = rng.next_below(1 << 9) as u16;
+        let err = ErrorGenerator::with_weights(&[1.0; 5], &mut rng).next_pattern();
+
+        ::encode(data) ^ err
+    }).collect::<Vec<_>>();
+
+    check_words(decoder, words)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sim::SplitMix64;
+
+    #[test]
+    fn test_decode() {
+        for (d, w) in codewords().enumerate() {
+            assert_eq!(decode(w), Some((d as u16, 0)));
+            assert_eq!(decode(w ^ 0b10000000000000100), Some((d as u16, 2)));
+            assert_ne!(decode(w ^ 0b10000000000010100).map(|(d, _)| d), Some(d as u16));
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check_words(::decode, 0..1 << 12), Ok(()));
+        assert_eq!(check_random(::decode, SplitMix64::new(5), 10000), Ok(()));
+
+        assert_eq!(check_words(|_| None, vec![0b11, 0]), Err(Mismatch {
+            word: 0b11,
+            expected: Some((0, 2)),
+            actual: None,
+        }));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_check_exhaustive() {
+        assert_eq!(check_exhaustive(::decode), Ok(()));
+    }
+}
@@ -0,0 +1,177 @@
+//! Time-diversity repetition of codewords.
+//!
+//! Outside of DMR and P25, the base code can be made more robust on fading channels by
+//! sending each codeword several times, spread out in time so a single fade is unlikely
+//! to hit every copy. The copies are then combined by majority vote or LLR summing, as
+//! in the [`vote`](../vote/index.html) module.
+//!
+//! Words are sent in blocks of `spacing` words, and each block is repeated `copies`
+//! times in a row, so the copies of a word are `spacing` slots apart. The final block
+//! may be shorter, in which case its copies are closer together.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::repeat::Repeat;
+//!
+//! let rep = Repeat::new(3, 4);
+//! let mut tx = rep.encode(&[1, 2, 3, 4, 5]);
+//! assert_eq!(tx.len(), 15);
+//!
+//! // A fade wipes out 4 consecutive slots, which hits only one copy of each word.
+//! for w in &mut tx[2..6] {
+//!     *w ^= 0x1ffff;
+//! }
+//!
+//! let rx = rep.decode(&tx);
+//! assert_eq!(rx.iter().map(|r| r.map(|(d, _)| d)).collect::<Vec<_>>(),
+//!            vec![Some(1), Some(2), Some(3), Some(4), Some(5)]);
+//! ```
+
+use soft::SoftBits;
+use vote;
+
+/// Repetition layout, giving the number of copies of each word and their spacing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Repeat {
+    /// Number of times each word is sent.
+    copies: usize,
+    /// Number of slots between copies of a word.
+    spacing: usize,
+}
+
+impl Repeat {
+    /// Create a new layout sending each word `copies` times, with copies `spacing`
+    /// slots apart.
+    pub fn new(copies: usize, spacing: usize) -> Self {
+        assert!(copies > 0);
+        assert!(spacing > 0);
+
+        Repeat { copies, spacing }
+    }
+
+    /// Number of times each word is sent.
+    pub fn copies(&self) -> usize { self.copies }
+
+    /// Number of slots between copies of a word.
+    pub fn spacing(&self) -> usize { self.spacing }
+
+    /// Encode the given data words and lay out their repeated codewords in
+    /// transmission order.
+    pub fn encode(&self, data: &[u16]) -> Vec<u32> {
+        data.chunks(self.spacing)
+            .flat_map(|block| (0..self.copies).flat_map(move |_| block.iter().map(|&d| {
+                ::encode(d)
+            })))
+            .collect()
+    }
+
+    /// Combine the copies of each received word by a per-bit majority vote and decode
+    /// them, with results as for [`vote::majority`](../vote/fn.majority.html).
+    ///
+    /// The number of received words must be a multiple of the number of copies.
+    pub fn decode(&self, words: &[u32]) -> Vec<Option<(u16, usize)>> {
+        (0..self.count(words.len()))
+            .map(|i| {
+                let copies = self.slots(words.len(), i).map(|s| words[s]).collect::<Vec<_>>();
+                vote::majority(&copies)
+            })
+            .collect()
+    }
+
+    /// Combine the copies of each received word by summing their LLRs and decode them,
+    /// with results as for [`vote::combine_soft`](../vote/fn.combine_soft.html).
+    ///
+    /// The number of received words must be a multiple of the number of copies.
+    pub fn decode_soft<S: SoftBits>(&self, words: &[S]) -> Vec<Option<(u16, i32)>> {
+        (0..self.count(words.len()))
+            .map(|i| {
+                let copies = self.slots(words.len(), i).map(|s| &words[s]).collect::<Vec<_>>();
+                vote::combine_soft(&copies)
+            })
+            .collect()
+    }
+
+    /// Number of data words carried by the given number of slots.
+    fn count(&self, slots: usize) -> usize {
+        assert_eq!(slots % self.copies, 0);
+        slots / self.copies
+    }
+
+    /// Slots holding the copies of the given data word.
+    fn slots(&self, slots: usize, word: usize) -> impl Iterator<Item = usize> {
+        let count = self.count(slots);
+        let block = word / self.spacing;
+        let start = block * self.spacing;
+        let len = (count - start).min(self.spacing);
+        let base = start * self.copies + word - start;
+
+        (0..self.copies).map(move |c| base + c * len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use encode;
+
+    #[test]
+    fn test_layout() {
+        let rep = Repeat::new(2, 3);
+        let w = encode;
+
+        assert_eq!(rep.encode(&[1, 2, 3, 4]), vec![w(1), w(2), w(3), w(1), w(2), w(3),
+                                                   w(4), w(4)]);
+        assert_eq!(Repeat::new(3, 1).encode(&[1, 2]), vec![w(1), w(1), w(1),
+                                                           w(2), w(2), w(2)]);
+        assert_eq!(Repeat::new(1, 5).encode(&[1, 2]), vec![w(1), w(2)]);
+        assert_eq!(rep.encode(&[]), vec![]);
+
+        assert_eq!(rep.slots(8, 1).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(rep.slots(8, 3).collect::<Vec<_>>(), vec![6, 7]);
+    }
+
+    #[test]
+    fn test_decode() {
+        let rep = Repeat::new(3, 2);
+        let data = [0b111000111, 0b000111000, 0b101010101];
+        let mut tx = rep.encode(&data);
+
+        // Three errors per copy, in different places.
+        for (i, w) in tx.iter_mut().enumerate() {
+            *w ^= 0b111 << (i % 3 * 5);
+        }
+
+        assert_eq!(rep.decode(&tx), vec![Some((0b111000111, 0)), Some((0b000111000, 0)),
+                                         Some((0b101010101, 0))]);
+        assert_eq!(rep.decode(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_decode_soft() {
+        let rep = Repeat::new(2, 2);
+        let tx = rep.encode(&[0b110110110, 0b001001001]);
+
+        let mut llrs = tx.iter().map(|&w| {
+            let mut llrs = [0i8; 17];
+
+            for (i, llr) in llrs.iter_mut().enumerate() {
+                *llr = if w >> (16 - i) & 1 == 1 { -50 } else { 50 };
+            }
+
+            llrs
+        }).collect::<Vec<_>>();
+
+        // Erase the first copy of the first word entirely.
+        llrs[0] = [0; 17];
+
+        assert_eq!(rep.decode_soft(&llrs), vec![Some((0b110110110, 0)),
+                                                Some((0b001001001, 0))]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_partial() {
+        Repeat::new(3, 2).decode(&[0; 4]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Shortened and extended variants of the base code.
+//!
+//! A variant is described by the number of data MSBs deleted from the base (17, 9, 5)
+//! code, which are fixed at zero and never sent, and an optional extra parity bit
+//! appended in the LSB, computed over the remaining data bits with a given mask. The DMR
+//! and P25 codes are both of this form, and other protocols use their own choices, so
+//! [`Shortened`](struct.Shortened.html) handles them all with one engine:
+//!
+//! - The DMR (16, 7, 6) code deletes 2 MSBs and adds a parity bit with mask `1010111`,
+//!   available as [`Shortened::DMR`](struct.Shortened.html#associatedconstant.DMR).
+//! - The P25 (16, 8, 5) code deletes 1 MSB with no extra parity bit, available as
+//!   [`Shortened::P25`](struct.Shortened.html#associatedconstant.P25).
+//! - The extended (18, 9, 6) code deletes nothing and adds the overall parity bit,
+//!   which is the parity of the data bits with mask `111010111`, available as
+//!   [`Shortened::EXTENDED`](struct.Shortened.html#associatedconstant.EXTENDED).
+//!
+//! Decoding corrects up to 2 errors, and rejects any correction into the deleted bits.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::shortened::Shortened;
+//!
+//! // A 15-bit variant carrying 6 data bits.
+//! let code = Shortened::new(3, Some(0b110011));
+//! assert_eq!((code.data_bits(), code.word_bits()), (6, 15));
+//!
+//! let w = code.encode(0b101101);
+//! assert_eq!(code.decode(w ^ 0b100000001), Some((0b101101, 2)));
+//!
+//! assert_eq!(Shortened::DMR.encode(0x55), cai_cyclic::encode_dmr(0x55) as u32);
+//! ```
+
+use code::{DynCode, Encoder, Decoder, from_bits, to_bits};
+use {decode, encode};
+
+/// A shortened base code, optionally with an extra parity bit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortened {
+    /// Number of deleted data MSBs.
+    deleted: u32,
+    /// Mask over the data bits for the extra parity bit, if any.
+    parity_mask: Option<u16>,
+}
+
+impl Shortened {
+    /// The DMR (16, 7, 6) quadrature residue code, as in
+    /// [`encode_dmr`](../fn.encode_dmr.html).
+    pub const DMR: Shortened = Shortened::new(2, Some(0b1010111));
+
+    /// The P25 (16, 8, 5) shortened cyclic code, as in
+    /// [`encode_p25`](../fn.encode_p25.html).
+    pub const P25: Shortened = Shortened::new(1, None);
+
+    /// The extended (18, 9, 6) code, as in
+    /// [`encode_extended`](../fn.encode_extended.html).
+    pub const EXTENDED: Shortened = Shortened::new(0, Some(0b111010111));
+
+    /// Create a new variant deleting the given number of data MSBs, up to 8, and
+    /// appending an extra parity bit over the remaining data bits selected by the given
+    /// mask, if any.
+    pub const fn new(deleted_msbs: u32, extra_parity_mask: Option<u16>) -> Self {
+        assert!(deleted_msbs < 9);
+
+        if let Some(mask) = extra_parity_mask {
+            assert!(mask >> (9 - deleted_msbs) == 0, "parity mask covers deleted bits");
+        }
+
+        Shortened { deleted: deleted_msbs, parity_mask: extra_parity_mask }
+    }
+
+    /// Number of data bits.
+    pub fn data_bits(&self) -> u32 { 9 - self.deleted }
+
+    /// Number of codeword bits.
+    pub fn word_bits(&self) -> u32 {
+        17 - self.deleted + self.parity_mask.is_some() as u32
+    }
+
+    /// Encode the given data bits into a codeword.
+    pub fn encode(&self, data: u16) -> u32 {
+        assert_eq!(data >> self.data_bits(), 0);
+
+        // The deleted MSBs are zero, so they drop off the base codeword.
+        let word = encode(data);
+
+        match self.parity_mask {
+            Some(mask) => word << 1 | (data & mask).count_ones() & 1,
+            None => word,
+        }
+    }
+
+    /// Try to decode the given word, correcting up to 2 errors.
+    ///
+    /// If decoding was successful, return `Some((data, err))`, where `data` is the data
+    /// bits and `err` is the number of corrected bits, including any extra parity bit.
+    /// Otherwise, return `None` to indicate an unrecoverable error.
+    pub fn decode(&self, word: u32) -> Option<(u16, usize)> {
+        assert_eq!(word >> self.word_bits(), 0);
+
+        let base = if self.parity_mask.is_some() { word >> 1 } else { word };
+        let (data, _) = decode(base)?;
+
+        // A correction into the deleted bits can't come from a codeword of the variant.
+        if data >> self.data_bits() != 0 {
+            return None;
+        }
+
+        // The base decoder ignores the extra parity bit, so count errors against the
+        // full re-encoded word.
+        match (self.encode(data) ^ word).count_ones() as usize {
+            err if err <= 2 => Some((data, err)),
+            _ => None,
+        }
+    }
+}
+
+impl Encoder for Shortened {
+    type Data = u16;
+    type Codeword = u32;
+
+    fn encode(&self, data: u16) -> u32 { Shortened::encode(self, data) }
+}
+
+impl Decoder for Shortened {
+    fn decode(&self, word: u32) -> Option<(u16, usize)> { Shortened::decode(self, word) }
+}
+
+impl DynCode for Shortened {
+    fn name(&self) -> &'static str { "shortened" }
+
+    fn data_len(&self) -> Option<usize> { Some(self.data_bits() as usize) }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+        if data.len() != self.data_bits() as usize {
+            return None;
+        }
+
+        let word = Shortened::encode(self, from_bits(data) as u16);
+        Some(to_bits(word as u64, self.word_bits() as usize))
+    }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        if word.len() != self.word_bits() as usize {
+            return None;
+        }
+
+        Shortened::decode(self, from_bits(word) as u32)
+            .map(|(data, err)| (to_bits(data as u64, self.data_bits() as usize), err))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use code::BlockCode;
+    use {Cyclic, decode_dmr, decode_extended, decode_p25, encode_dmr, encode_extended,
+         encode_p25};
+
+    #[test]
+    fn test_params() {
+        assert_eq!((Shortened::DMR.data_bits(), Shortened::DMR.word_bits()), (7, 16));
+        assert_eq!((Shortened::P25.data_bits(), Shortened::P25.word_bits()), (8, 16));
+        assert_eq!((Shortened::EXTENDED.data_bits(), Shortened::EXTENDED.word_bits()),
+                   (9, 18));
+
+        let base = Shortened::new(0, None);
+        assert_eq!(base.word_bits() as usize, Cyclic::N);
+        assert_eq!(base.data_bits() as usize, Cyclic::K);
+    }
+
+    #[test]
+    fn test_known_variants() {
+        for d in 0..1 << 9 {
+            assert_eq!(Shortened::EXTENDED.encode(d), encode_extended(d));
+        }
+
+        for d in 0..1 << 8 {
+            assert_eq!(Shortened::P25.encode(d), encode_p25(d as u8) as u32);
+        }
+
+        for d in 0..1 << 7 {
+            assert_eq!(Shortened::DMR.encode(d), encode_dmr(d as u8) as u32);
+        }
+
+        for w in 0..1 << 16 {
+            let to_u16 = |r: Option<(u8, usize)>| r.map(|(d, e)| (d as u16, e));
+
+            assert_eq!(Shortened::P25.decode(w), to_u16(decode_p25(w as u16)));
+            assert_eq!(Shortened::DMR.decode(w), to_u16(decode_dmr(w as u16)));
+        }
+
+        for w in (0..1 << 18).step_by(5) {
+            assert_eq!(Shortened::EXTENDED.decode(w), decode_extended(w));
+        }
+    }
+
+    #[test]
+    fn test_custom() {
+        let code = Shortened::new(4, None);
+
+        for d in 0..1 << 5 {
+            let w = code.encode(d);
+            assert_eq!(w >> 13, 0);
+
+            for i in 0..13 {
+                assert_eq!(code.decode(w ^ 1 << i), Some((d, 1)));
+            }
+        }
+
+        let code: Box<dyn DynCode> = Box::new(Shortened::new(3, Some(0b11)));
+        let data = vec![true, false, true, true, false, true];
+        let mut word = code.encode(&data).unwrap();
+
+        assert_eq!(word.len(), 15);
+        word[14] = !word[14];
+        assert_eq!(code.decode(&word), Some((data, 1)));
+        assert_eq!(code.decode(&[false; 16]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "parity mask covers deleted bits")]
+    fn test_bad_mask() {
+        Shortened::new(2, Some(0b100000000));
+    }
+}
@@ -0,0 +1,524 @@
+//! Random error generation for Monte Carlo evaluation of the codes.
+//!
+//! All randomness comes from a caller-supplied [`RandomSource`](trait.RandomSource.html),
+//! so any run can be reproduced exactly from its seed. A small built-in
+//! [`SplitMix64`](struct.SplitMix64.html) generator is provided, and other generators
+//! can be plugged in by implementing the trait.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::sim::{ErrorGenerator, SplitMix64};
+//!
+//! // Half of all words get a single-bit error and half get a two-bit error.
+//! let mut errs = ErrorGenerator::with_weights(&[0.0, 1.0, 1.0], SplitMix64::new(42));
+//!
+//! for _ in 0..100 {
+//!     let w = cai_cyclic::encode(0b110011001) ^ errs.next_pattern();
+//!     assert_eq!(cai_cyclic::decode(w).map(|(d, _)| d), Some(0b110011001));
+//! }
+//! ```
+//!
+//! With the `sim` feature, [`run`](fn.run.html) drives a decoder over a channel with a
+//! given bit error rate and reports the error rates before and after decoding in a
+//! [`LinkStats`](struct.LinkStats.html). The feature also enables the
+//! [`BinarySymmetricChannel`](struct.BinarySymmetricChannel.html) model for hard
+//! decoders and the [`AwgnChannel`](struct.AwgnChannel.html) model, which produces LLRs
+//! for the soft decoders.
+
+/// Source of uniformly distributed random bits.
+pub trait RandomSource {
+    /// Generate the next 64 random bits.
+    fn next_u64(&mut self) -> u64;
+
+    /// Generate a random float uniformly distributed in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a random integer uniformly distributed in [0, n).
+    fn next_below(&mut self, n: u32) -> u32 {
+        assert!(n > 0);
+        (((self.next_u64() >> 32) * n as u64) >> 32) as u32
+    }
+}
+
+impl<R: RandomSource + ?Sized> RandomSource for &mut R {
+    fn next_u64(&mut self) -> u64 { (**self).next_u64() }
+}
+
+/// The SplitMix64 pseudorandom generator.
+///
+/// This is fast and statistically solid for simulation, but not cryptographically
+/// secure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Create a new generator from the given seed.
+    pub fn new(seed: u64) -> Self { SplitMix64(seed) }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+
+        let z = self.0;
+        let z = (z ^ z >> 30).wrapping_mul(0xbf58476d1ce4e5b9);
+        let z = (z ^ z >> 27).wrapping_mul(0x94d049bb133111eb);
+
+        z ^ z >> 31
+    }
+}
+
+/// Distribution that error patterns are drawn from.
+#[derive(Clone, Debug, PartialEq)]
+enum Model {
+    /// Cumulative probability of each error weight.
+    Weights(Vec<f64>),
+    /// Probability of each bit being flipped, indexed from the LSB.
+    BitErrorRates([f64; 17]),
+}
+
+/// Generates random 17-bit error patterns following a chosen distribution.
+#[derive(Clone, Debug)]
+pub struct ErrorGenerator<R: RandomSource> {
+    model: Model,
+    rng: R,
+}
+
+impl<R: RandomSource> ErrorGenerator<R> {
+    /// Create a generator where each pattern has weight `w` with probability
+    /// proportional to `weights[w]`, with the flipped bits chosen uniformly.
+    ///
+    /// Weights beyond the end of the slice have zero probability.
+    pub fn with_weights(weights: &[f64], rng: R) -> Self {
+        assert!(weights.len() <= 18);
+        assert!(weights.iter().all(|&w| w >= 0.0));
+
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0);
+
+        let cdf = weights.iter()
+            .scan(0.0, |sum, &w| {
+                *sum += w / total;
+                Some(*sum)
+            })
+            .collect();
+
+        ErrorGenerator {
+            model: Model::Weights(cdf),
+            rng,
+        }
+    }
+
+    /// Create a generator where bit `i` (counting from the LSB) is flipped independently
+    /// with probability `ber[i]`.
+    pub fn with_ber_profile(ber: [f64; 17], rng: R) -> Self {
+        assert!(ber.iter().all(|&p| (0.0..=1.0).contains(&p)));
+
+        ErrorGenerator {
+            model: Model::BitErrorRates(ber),
+            rng,
+        }
+    }
+
+    /// Generate the next error pattern.
+    pub fn next_pattern(&mut self) -> u32 {
+        match self.model {
+            Model::Weights(ref cdf) => {
+                let x = self.rng.next_f64();
+                let weight = cdf.iter().position(|&c| x < c).unwrap_or(cdf.len() - 1);
+
+                random_pattern(&mut self.rng, weight)
+            },
+            Model::BitErrorRates(ref ber) => {
+                let rng = &mut self.rng;
+
+                ber.iter().enumerate().fold(0, |pat, (i, &p)| {
+                    if rng.next_f64() < p { pat | 1 << i } else { pat }
+                })
+            },
+        }
+    }
+
+    /// Consume the generator and return its random source.
+    pub fn into_inner(self) -> R { self.rng }
+}
+
+impl<R: RandomSource> Iterator for ErrorGenerator<R> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> { Some(self.next_pattern()) }
+}
+
+/// Generate a uniformly random 17-bit pattern with the given weight.
+fn random_pattern<R: RandomSource>(rng: &mut R, weight: usize) -> u32 {
+    assert!(weight <= 17);
+
+    let mut pos = [0u8; 17];
+
+    for (i, p) in pos.iter_mut().enumerate() {
+        *p = i as u8;
+    }
+
+    // Partial Fisher-Yates shuffle to choose the flipped positions.
+    (0..weight).fold(0, |pat, i| {
+        let j = i + rng.next_below((17 - i) as u32) as usize;
+        pos.swap(i, j);
+        pat | 1 << pos[i]
+    })
+}
+
+/// Channel that flips each bit independently with a fixed probability.
+#[cfg(feature = "sim")]
+#[derive(Clone, Debug)]
+pub struct BinarySymmetricChannel<R: RandomSource> {
+    /// Crossover probability.
+    p: f64,
+    rng: R,
+}
+
+#[cfg(feature = "sim")]
+impl<R: RandomSource> BinarySymmetricChannel<R> {
+    /// Create a channel with the given crossover probability.
+    pub fn new(p: f64, rng: R) -> Self {
+        assert!((0.0..=1.0).contains(&p));
+        BinarySymmetricChannel { p, rng }
+    }
+
+    /// Pass the given 17-bit word through the channel.
+    pub fn corrupt(&mut self, word: u32) -> u32 {
+        assert_eq!(word >> 17, 0);
+        self.corrupt_bits(word as u64, 17) as u32
+    }
+
+    /// Pass the given `n`-bit word, for `n` up to 64, through the channel.
+    pub fn corrupt_bits(&mut self, word: u64, n: usize) -> u64 {
+        assert!(n <= 64);
+
+        (0..n).fold(word, |word, i| {
+            if self.rng.next_f64() < self.p { word ^ 1 << i } else { word }
+        })
+    }
+
+    /// Consume the channel and return its random source.
+    pub fn into_inner(self) -> R { self.rng }
+}
+
+/// Channel carrying BPSK symbols, +1 for a 0 bit and -1 for a 1 bit, through additive
+/// white Gaussian noise.
+///
+/// Samples from other constellations, such as the 4-level symbols of DMR and P25, can be
+/// sent through [`pass`](#method.pass) on a channel created for their signal power.
+#[cfg(feature = "sim")]
+#[derive(Clone, Debug)]
+pub struct AwgnChannel<R: RandomSource> {
+    rng: R,
+    /// Noise standard deviation per sample.
+    sigma: f64,
+    /// Second sample left over from the last Box-Muller transform.
+    spare: Option<f64>,
+}
+
+#[cfg(feature = "sim")]
+impl<R: RandomSource> AwgnChannel<R> {
+    /// Create a channel with the given SNR per symbol in dB.
+    pub fn new(snr_db: f64, rng: R) -> Self { Self::with_signal_power(snr_db, 1.0, rng) }
+
+    /// Create a channel with the given SNR per symbol in dB for symbols of the given
+    /// average power, such as 5 for the levels ±1 and ±3.
+    pub fn with_signal_power(snr_db: f64, signal_power: f64, rng: R) -> Self {
+        assert!(signal_power > 0.0);
+
+        AwgnChannel {
+            rng,
+            sigma: (signal_power / 10f64.powf(snr_db / 10.0)).sqrt(),
+            spare: None,
+        }
+    }
+
+    /// Noise variance per sample, for scaling received samples into LLRs.
+    pub fn noise_var(&self) -> f64 { self.sigma * self.sigma }
+
+    /// Transmit the given bit and return the received sample.
+    pub fn sample(&mut self, bit: bool) -> f64 { self.pass(if bit { -1.0 } else { 1.0 }) }
+
+    /// Transmit the given sample and return the received sample.
+    pub fn pass(&mut self, sample: f64) -> f64 { sample + self.sigma * self.gaussian() }
+
+    /// Transmit the given 17-bit word and return the LLR of each received bit, in
+    /// transmission order, ready for [`soft::decode`](../soft/fn.decode.html).
+    pub fn llrs(&mut self, word: u32) -> [f32; 17] {
+        assert_eq!(word >> 17, 0);
+
+        let mut llrs = [0.0; 17];
+        self.llrs_into(word as u64, &mut llrs);
+
+        llrs
+    }
+
+    /// Transmit the given word of `llrs.len()` bits, up to 64, and write the LLR of each
+    /// received bit in transmission order.
+    pub fn llrs_into(&mut self, word: u64, llrs: &mut [f32]) {
+        let n = llrs.len();
+        assert!(n <= 64);
+
+        for (i, llr) in llrs.iter_mut().enumerate() {
+            let y = self.sample(word >> (n - 1 - i) & 1 == 1);
+            *llr = (2.0 * y / self.noise_var()) as f32;
+        }
+    }
+
+    /// Consume the channel and return its random source.
+    pub fn into_inner(self) -> R { self.rng }
+
+    /// Draw a standard normal sample.
+    fn gaussian(&mut self) -> f64 {
+        if let Some(x) = self.spare.take() {
+            return x;
+        }
+
+        // Keep u away from 0 so the log is finite.
+        let u = 1.0 - self.rng.next_f64();
+        let v = self.rng.next_f64();
+        let r = (-2.0 * u.ln()).sqrt();
+        let theta = 2.0 * std::f64::consts::PI * v;
+
+        self.spare = Some(r * theta.sin());
+        r * theta.cos()
+    }
+}
+
+/// Bit and frame error counts from a simulation run.
+#[cfg(feature = "sim")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkStats {
+    /// Number of codewords sent.
+    pub frames: u64,
+    /// Bits flipped by the channel, over all 17 bits of each codeword.
+    pub channel_bit_errors: u64,
+    /// Codewords with at least one bit flipped by the channel.
+    pub channel_frame_errors: u64,
+    /// Data bits wrong after decoding, where a rejected word passes on its received
+    /// data bits uncorrected.
+    pub data_bit_errors: u64,
+    /// Codewords rejected by the decoder.
+    pub detected: u64,
+    /// Codewords decoded to the wrong data.
+    pub miscorrected: u64,
+}
+
+#[cfg(feature = "sim")]
+impl LinkStats {
+    /// Fraction of codeword bits flipped by the channel.
+    pub fn channel_ber(&self) -> f64 { ratio(self.channel_bit_errors, 17 * self.frames) }
+
+    /// Fraction of codewords with any bit flipped by the channel.
+    pub fn channel_fer(&self) -> f64 { ratio(self.channel_frame_errors, self.frames) }
+
+    /// Fraction of data bits wrong after decoding.
+    pub fn ber(&self) -> f64 { ratio(self.data_bit_errors, 9 * self.frames) }
+
+    /// Fraction of codewords that weren't decoded to the right data.
+    pub fn fer(&self) -> f64 { ratio(self.detected + self.miscorrected, self.frames) }
+}
+
+/// Send the given number of random codewords through a channel that flips each bit
+/// independently with probability `ber`, decode them with the given decoder, and count
+/// the errors before and after decoding.
+///
+/// The data words and error patterns are both drawn from `rng`, so a run is reproduced
+/// exactly by its seed.
+///
+/// ```rust
+/// use cai_cyclic::sim::{self, SplitMix64};
+///
+/// let stats = sim::run(cai_cyclic::decode, 0.01, 10000, SplitMix64::new(1));
+///
+/// assert!((stats.channel_ber() - 0.01).abs() < 0.002);
+/// assert!(stats.fer() < stats.channel_fer() / 10.0);
+/// ```
+#[cfg(feature = "sim")]
+pub fn run<F, R>(mut decoder: F, ber: f64, frames: u64, mut rng: R) -> LinkStats where
+    F: FnMut(u32) -> Option<(u16, usize)>,
+    R: RandomSource,
+{
+    let mut chan = BinarySymmetricChannel::new(ber, SplitMix64::new(rng.next_u64()));
+    let mut stats = LinkStats { frames, ..LinkStats::default() };
+
+    for _ in 0..frames {
+        let data = rng.next_below(1 << 9) as u16;
+        let sent = ::encode(data);
+        let word = chan.corrupt(sent);
+        let pat = word ^ sent;
+
+        stats.channel_bit_errors += pat.count_ones() as u64;
+        stats.channel_frame_errors += (pat != 0) as u64;
+
+        let out = match decoder(word) {
+            Some((d, _)) => {
+                stats.miscorrected += (d != data) as u64;
+                d
+            },
+            None => {
+                stats.detected += 1;
+                (word >> 8) as u16
+            },
+        };
+
+        stats.data_bit_errors += (out ^ data).count_ones() as u64;
+    }
+
+    stats
+}
+
+/// Divide the given counts, treating an empty denominator as a zero rate.
+#[cfg(feature = "sim")]
+fn ratio(num: u64, den: u64) -> f64 {
+    if den == 0 { 0.0 } else { num as f64 / den as f64 }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_splitmix() {
+        let mut a = SplitMix64::new(1234);
+        let mut b = SplitMix64::new(1234);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+
+        for _ in 0..1000 {
+            let x = a.next_f64();
+            assert!((0.0..1.0).contains(&x));
+            assert!(a.next_below(17) < 17);
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        let mut g = ErrorGenerator::with_weights(&[0.0, 0.0, 0.0, 1.0], SplitMix64::new(7));
+
+        for _ in 0..1000 {
+            let p = g.next_pattern();
+            assert_eq!(p.count_ones(), 3);
+            assert_eq!(p >> 17, 0);
+        }
+
+        let counts = ErrorGenerator::with_weights(&[1.0, 3.0], SplitMix64::new(7))
+            .take(10000)
+            .fold([0; 2], |mut c, p| {
+                c[p.count_ones() as usize] += 1;
+                c
+            });
+
+        assert!(counts[0] > 2200 && counts[0] < 2800);
+        assert_eq!(counts[0] + counts[1], 10000);
+    }
+
+    #[test]
+    fn test_ber_profile() {
+        let mut ber = [0.0; 17];
+        ber[0] = 1.0;
+        ber[16] = 0.5;
+
+        let pats = ErrorGenerator::with_ber_profile(ber, SplitMix64::new(99))
+            .take(10000)
+            .collect::<Vec<_>>();
+
+        assert!(pats.iter().all(|&p| p & !(1 | 1 << 16) == 0 && p & 1 == 1));
+
+        let msb = pats.iter().filter(|&&p| p >> 16 == 1).count();
+        assert!(msb > 4700 && msb < 5300);
+    }
+
+    #[test]
+    #[cfg(feature = "sim")]
+    fn test_bsc() {
+        let mut chan = BinarySymmetricChannel::new(0.0, SplitMix64::new(3));
+        assert_eq!(chan.corrupt(0x1abcd), 0x1abcd);
+
+        let mut chan = BinarySymmetricChannel::new(1.0, SplitMix64::new(3));
+        assert_eq!(chan.corrupt(0x1abcd), !0x1abcd & 0x1ffff);
+        assert_eq!(chan.corrupt_bits(0, 64), !0);
+
+        let mut chan = BinarySymmetricChannel::new(0.25, SplitMix64::new(3));
+        let flips = (0..1000).map(|_| chan.corrupt(0).count_ones()).sum::<u32>();
+        assert!(flips > 4000 && flips < 4500);
+
+        let mut a = BinarySymmetricChannel::new(0.1, SplitMix64::new(8));
+        let mut b = BinarySymmetricChannel::new(0.1, SplitMix64::new(8));
+        assert!((0..100).all(|_| a.corrupt(0) == b.corrupt(0)));
+    }
+
+    #[test]
+    #[cfg(feature = "sim")]
+    fn test_awgn() {
+        let w = ::encode(0b101011010);
+
+        // At high SNR, every LLR has the sign of its bit and a large magnitude.
+        let mut chan = AwgnChannel::new(20.0, SplitMix64::new(4));
+        assert!((chan.noise_var() - 0.01).abs() < 1e-12);
+
+        let llrs = chan.llrs(w);
+        assert!(llrs.iter().enumerate().all(|(i, &llr)| (llr < 0.0) == (w >> (16 - i) & 1 == 1)));
+        assert!(llrs.iter().all(|llr| llr.abs() > 100.0));
+
+        // The samples have unit mean and the configured noise variance.
+        let mut chan = AwgnChannel::new(3.0, SplitMix64::new(4));
+        let samples = (0..20000).map(|_| chan.sample(false)).collect::<Vec<_>>();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let var = samples.iter().map(|y| (y - mean) * (y - mean)).sum::<f64>() /
+            samples.len() as f64;
+
+        assert!((mean - 1.0).abs() < 0.02);
+        assert!((var - chan.noise_var()).abs() < 0.02);
+
+        // The noise scales with the signal power.
+        let mut chan = AwgnChannel::with_signal_power(3.0, 5.0, SplitMix64::new(4));
+        assert!((chan.noise_var() - 5.0 / 10f64.powf(0.3)).abs() < 1e-12);
+
+        let samples = (0..20000).map(|_| chan.pass(-3.0)).collect::<Vec<_>>();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((mean + 3.0).abs() < 0.05);
+
+        // Soft decoding recovers nearly every word at moderate SNR.
+        let mut chan = AwgnChannel::new(4.0, SplitMix64::new(4));
+        let bad = (0..1000)
+            .filter(|_| ::soft::decode(&chan.llrs(w)).map(|(d, _)| d) != Some(0b101011010))
+            .count();
+        assert!(bad < 10);
+
+        let mut llrs = [0.0; 18];
+        AwgnChannel::new(20.0, SplitMix64::new(4)).llrs_into(::extend(w) as u64, &mut llrs);
+        assert_eq!(::soft::decode_extended(&llrs[..]).map(|(d, _)| d), Some(0b101011010));
+    }
+
+    #[test]
+    #[cfg(feature = "sim")]
+    fn test_run() {
+        let stats = run(::decode, 0.0, 1000, SplitMix64::new(5));
+        assert_eq!(stats, LinkStats { frames: 1000, ..LinkStats::default() });
+        assert_eq!((stats.ber(), stats.fer()), (0.0, 0.0));
+
+        // Every word is hit, but the decoder fixes the sparse errors.
+        let stats = run(::decode, 0.02, 20000, SplitMix64::new(5));
+        assert_eq!(stats, run(::decode, 0.02, 20000, SplitMix64::new(5)));
+        assert!((stats.channel_ber() - 0.02).abs() < 0.002);
+        assert!(stats.channel_fer() > 0.25);
+        assert!(stats.fer() < 0.01);
+        assert!(stats.ber() < stats.channel_ber());
+
+        // A decoder that gives up on everything passes the raw data bits through.
+        let stats = run(|_| None, 0.1, 20000, SplitMix64::new(5));
+        assert_eq!(stats.detected, 20000);
+        assert_eq!(stats.miscorrected, 0);
+        assert!((stats.ber() - 0.1).abs() < 0.01);
+
+        assert_eq!(LinkStats::default().fer(), 0.0);
+    }
+}
@@ -0,0 +1,215 @@
+//! Lane-parallel batch decoder using `std::simd`.
+//!
+//! The scalar decoder handles one word at a time, with a branch on its syndrome, which
+//! leaves most of a wide core idle. This decoder instead works on 16 words at once, one
+//! per lane: it computes all 16 syndromes with lane-wise parity of the parity-check
+//! rows, gathers their error patterns from the syndrome table, and blends the
+//! corrections in with masks, so there are no per-word branches.
+//!
+//! This requires a nightly compiler for the unstable `portable_simd` library feature.
+//!
+//! [`decode_batch_simd`](fn.decode_batch_simd.html) is compiled for the build's target
+//! features, which for a generic x86-64 binary means plain SSE2.
+//! [`decode_batch`](fn.decode_batch.html) instead detects the CPU at runtime, and runs
+//! the same decoder compiled for AVX-512 or AVX2 where available, or on other CPUs the
+//! scalar [`decode_slice`](../fn.decode_slice.html). NEON is always available on
+//! AArch64, so there it runs the vector decoder directly.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::simd::decode_batch_simd;
+//!
+//! let words = (0..40).map(|d| cai_cyclic::encode(d) ^ 0b101).collect::<Vec<_>>();
+//! let mut out = vec![None; words.len()];
+//!
+//! decode_batch_simd(&words, &mut out);
+//!
+//! assert!(out.iter().enumerate().all(|(d, &r)| r == Some((d as u16, 2))));
+//! ```
+
+use std::simd::{Select, Simd};
+use std::simd::cmp::SimdPartialEq;
+use std::simd::num::SimdUint;
+
+use {PAR, PATTERNS};
+
+/// Number of words decoded in parallel.
+pub const LANES: usize = 16;
+
+/// Vector of one word per lane.
+type Words = Simd<u32, LANES>;
+
+/// Decode each of the given 17-bit words into the corresponding output slot, with the
+/// same results as [`decode`](../fn.decode.html).
+///
+/// Words are processed in groups of [`LANES`](constant.LANES.html), and a final
+/// partial group is padded internally, so any number of words is accepted.
+///
+/// With the `cross-check` feature, each result is also compared against the scalar
+/// decoder, panicking with the word, both results, and its lane if they differ.
+pub fn decode_batch_simd(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    decode_chunks(words, out);
+}
+
+/// Decode each of the given 17-bit words as in
+/// [`decode_batch_simd`](fn.decode_batch_simd.html), with the fastest decoder supported
+/// by the running CPU.
+pub fn decode_batch(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx512f") {
+            return unsafe { decode_batch_avx512(words, out) };
+        }
+
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { decode_batch_avx2(words, out) };
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    return decode_chunks(words, out);
+
+    #[cfg(not(target_arch = "aarch64"))]
+    ::decode_slice(words, out);
+}
+
+/// Decode with the vector decoder compiled for AVX-512.
+///
+/// The CPU must support AVX-512F.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512f")]
+unsafe fn decode_batch_avx512(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    decode_chunks(words, out);
+}
+
+/// Decode with the vector decoder compiled for AVX2.
+///
+/// The CPU must support AVX2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_batch_avx2(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    decode_chunks(words, out);
+}
+
+/// Decode the given words in groups of `LANES`, inlined into each caller so it's
+/// compiled for that caller's target features.
+#[inline(always)]
+fn decode_chunks(words: &[u32], out: &mut [Option<(u16, usize)>]) {
+    assert_eq!(words.len(), out.len());
+
+    for (words, out) in words.chunks(LANES).zip(out.chunks_mut(LANES)) {
+        assert!(words.iter().all(|&w| w >> 17 == 0));
+
+        let (data, err) = decode_lanes(Words::load_or_default(words));
+
+        for (lane, slot) in out.iter_mut().enumerate() {
+            *slot = match err[lane] {
+                u32::MAX => None,
+                err => Some((data[lane] as u16, err as usize)),
+            };
+
+            #[cfg(feature = "cross-check")]
+            ::cross_check("simd", words[lane], *slot, format_args!("lane {}", lane));
+        }
+    }
+}
+
+/// Decode a full vector of words, returning the data in each lane and the number of
+/// corrected errors, or `u32::MAX` for a lane that couldn't be decoded.
+#[inline(always)]
+fn decode_lanes(word: Words) -> (Words, Words) {
+    let zero = Words::splat(0);
+
+    let syndrome = syndrome_lanes(word);
+    let pat = Words::gather_or_default(&PATTERNS, syndrome.cast());
+
+    let fixed = pat.simd_ne(zero).select(pat.count_ones(), Words::splat(u32::MAX));
+    let err = syndrome.simd_ne(zero).select(fixed, zero);
+
+    ((word ^ pat) >> 8, err)
+}
+
+/// Compute the 8-bit syndrome of the word in each lane.
+#[inline(always)]
+fn syndrome_lanes(word: Words) -> Words {
+    PAR.iter().fold(Words::splat(0), |syndrome, &row| {
+        syndrome << 1 | (word & Words::splat(row)).count_ones() & Words::splat(1)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {decode, syndrome};
+    use sim::{RandomSource, SplitMix64};
+
+    #[test]
+    fn test_syndrome() {
+        let words = Words::from_array(std::array::from_fn(|i| (i as u32 * 7919) & 0x1ffff));
+        let s = syndrome_lanes(words);
+
+        for lane in 0..LANES {
+            assert_eq!(s[lane], syndrome(words[lane]) as u32);
+        }
+    }
+
+    #[test]
+    fn test_exhaustive() {
+        let words = (0..1 << 17).collect::<Vec<u32>>();
+        let mut out = vec![None; words.len()];
+
+        decode_batch_simd(&words, &mut out);
+
+        assert!(words.iter().zip(&out).all(|(&w, &r)| r == decode(w)));
+    }
+
+    #[test]
+    fn test_partial() {
+        let mut rng = SplitMix64::new(5);
+
+        for len in 0..3 * LANES {
+            let words = (0..len).map(|_| rng.next_u64() as u32 & 0x1ffff).collect::<Vec<_>>();
+            let mut out = vec![Some((0x1ff, 9)); len];
+
+            decode_batch_simd(&words, &mut out);
+
+            assert!(words.iter().zip(&out).all(|(&w, &r)| r == decode(w)));
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let mut rng = SplitMix64::new(7);
+        let words = (0..1000).map(|_| rng.next_u64() as u32 & 0x1ffff).collect::<Vec<_>>();
+        let expected = words.iter().map(|&w| decode(w)).collect::<Vec<_>>();
+
+        let mut out = vec![None; words.len()];
+        decode_batch(&words, &mut out);
+        assert_eq!(out, expected);
+
+        // Check each backend the test machine supports, not just the one chosen.
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx512f") {
+                let mut out = vec![None; words.len()];
+                unsafe { decode_batch_avx512(&words, &mut out) };
+                assert_eq!(out, expected);
+            }
+
+            if is_x86_feature_detected!("avx2") {
+                let mut out = vec![None; words.len()];
+                unsafe { decode_batch_avx2(&words, &mut out) };
+                assert_eq!(out, expected);
+            }
+        }
+
+        decode_batch(&[], &mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_length_mismatch() {
+        decode_batch_simd(&[0; 4], &mut [None; 3]);
+    }
+}
@@ -0,0 +1,666 @@
+//! Soft-decision decoding of the base (17, 9, 5) code.
+//!
+//! Soft bits are log-likelihood ratios in transmission order, so index 0 holds the MSB
+//! of the codeword. Positive values favor a 0 bit, negative values favor a 1 bit, and 0
+//! is an erasure, as is a NaN floating-point LLR. Soft bits can be given as `i8`,
+//! `i16`, or `f32` arrays and slices, and any other demodulator output can be used by
+//! implementing the [`SoftBits`](trait.SoftBits.html) trait.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::soft;
+//!
+//! let w = cai_cyclic::encode(0b011001110);
+//! let mut llrs = [0i8; 17];
+//!
+//! for (i, llr) in llrs.iter_mut().enumerate() {
+//!     *llr = if w >> (16 - i) & 1 == 1 { -90 } else { 90 };
+//! }
+//!
+//! // Three errors are beyond the hard decoder, but they're on the least reliable bits.
+//! for &i in &[0, 8, 16] {
+//!     llrs[i] = -llrs[i] / 9;
+//! }
+//!
+//! assert_eq!(soft::decode(&llrs), Some((0b011001110, 30)));
+//! ```
+
+use {codewords, encode, extend, is_codeword};
+
+/// A source of soft bits for a received word.
+pub trait SoftBits {
+    /// Number of soft bits available.
+    fn len(&self) -> usize;
+
+    /// Log-likelihood ratio of the given bit, in transmission order, clamped to
+    /// `±full_scale()`.
+    fn llr(&self, bit: usize) -> i32;
+
+    /// Magnitude representing a certain decision.
+    ///
+    /// This gives the scale of the values returned by `llr()`, so results from different
+    /// demodulators can be compared.
+    fn full_scale(&self) -> i32;
+
+    /// Check if there are no soft bits.
+    fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Hard decision of the given bit.
+    fn hard(&self, bit: usize) -> bool { self.llr(bit) < 0 }
+}
+
+impl<S: SoftBits + ?Sized> SoftBits for &S {
+    fn len(&self) -> usize { (**self).len() }
+    fn llr(&self, bit: usize) -> i32 { (**self).llr(bit) }
+    fn full_scale(&self) -> i32 { (**self).full_scale() }
+}
+
+macro_rules! impl_soft_bits {
+    ($t:ty) => {
+        impl SoftBits for [$t] {
+            fn len(&self) -> usize { <[$t]>::len(self) }
+            fn llr(&self, bit: usize) -> i32 {
+                (self[bit] as i32).max(-(<$t>::MAX as i32))
+            }
+            fn full_scale(&self) -> i32 { <$t>::MAX as i32 }
+        }
+
+        impl SoftBits for [$t; 17] {
+            fn len(&self) -> usize { 17 }
+            fn llr(&self, bit: usize) -> i32 { self[..].llr(bit) }
+            fn full_scale(&self) -> i32 { self[..].full_scale() }
+        }
+    };
+}
+
+impl_soft_bits!(i8);
+impl_soft_bits!(i16);
+
+/// Largest magnitude of floating-point LLRs, beyond which they're clamped.
+pub const FLOAT_LIMIT: f32 = 64.0;
+
+/// Scale applied to floating-point LLRs to convert them to integers internally, so
+/// costs from float inputs are in units of 1/1024 of an LLR.
+pub const FLOAT_SCALE: f32 = 1024.0;
+
+impl SoftBits for [f32] {
+    fn len(&self) -> usize { <[f32]>::len(self) }
+
+    fn llr(&self, bit: usize) -> i32 {
+        // NaN maps to an erasure.
+        (self[bit].clamp(-FLOAT_LIMIT, FLOAT_LIMIT) * FLOAT_SCALE) as i32
+    }
+
+    fn full_scale(&self) -> i32 { (FLOAT_LIMIT * FLOAT_SCALE) as i32 }
+}
+
+impl SoftBits for [f32; 17] {
+    fn len(&self) -> usize { 17 }
+    fn llr(&self, bit: usize) -> i32 { self[..].llr(bit) }
+    fn full_scale(&self) -> i32 { self[..].full_scale() }
+}
+
+/// Soft bits with an explicit full-scale magnitude.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Scaled<'a> {
+    llrs: &'a [i32],
+    full_scale: i32,
+}
+
+impl<'a> Scaled<'a> {
+    /// Wrap the given LLRs, where a magnitude of `full_scale` represents a certain
+    /// decision. Larger magnitudes are clamped.
+    ///
+    /// Costs are summed without overflow but saturate at `i32::MAX`, so a `full_scale`
+    /// well below `i32::MAX / 196` keeps the costs of every decoder comparable.
+    pub fn new(llrs: &'a [i32], full_scale: i32) -> Self {
+        assert!(full_scale > 0);
+        Scaled { llrs, full_scale }
+    }
+}
+
+impl<'a> SoftBits for Scaled<'a> {
+    fn len(&self) -> usize { self.llrs.len() }
+
+    fn llr(&self, bit: usize) -> i32 {
+        self.llrs[bit].max(-self.full_scale).min(self.full_scale)
+    }
+
+    fn full_scale(&self) -> i32 { self.full_scale }
+}
+
+/// Converts raw demodulator samples into correctly scaled LLRs.
+///
+/// The LLR of a sample depends on the noise variance as well as the sample itself, so
+/// samples are scaled by a noise estimate rather than used directly. Magnitudes are
+/// saturated at a configurable limit, since an overconfident LLR from a mis-estimated
+/// channel easily outweighs several correct ones.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LlrConverter {
+    /// Noise variance per real sample.
+    noise_var: f32,
+    /// Largest LLR magnitude produced.
+    limit: f32,
+}
+
+impl LlrConverter {
+    /// Create a new converter for the given noise variance per sample, saturating LLRs
+    /// at a magnitude of 16.
+    pub fn new(noise_var: f32) -> Self {
+        assert!(noise_var > 0.0);
+        LlrConverter { noise_var, limit: 16.0 }
+    }
+
+    /// Create a new converter for the given SNR in dB, where `signal_power` is the
+    /// average power of the constellation: 1 for BPSK at ±1, or 5 for 4-level symbols at
+    /// ±1 and ±3.
+    pub fn from_snr_db(snr_db: f32, signal_power: f32) -> Self {
+        Self::new(signal_power / 10f32.powf(snr_db / 10.0))
+    }
+
+    /// Change the LLR magnitude where outputs saturate.
+    pub fn with_limit(self, limit: f32) -> Self {
+        assert!(limit > 0.0);
+        LlrConverter { limit, ..self }
+    }
+
+    /// Convert a binary sample, where +1 represents a 0 bit and -1 represents a 1 bit.
+    pub fn binary(&self, sample: f32) -> f32 {
+        self.saturate(2.0 * sample / self.noise_var)
+    }
+
+    /// Convert a 4-level symbol sample into LLRs for its `(msb, lsb)` bits, using the
+    /// DMR/P25 mapping of 01 → +3, 00 → +1, 10 → -1, 11 → -3.
+    ///
+    /// This uses the max-log approximation, taking the nearest symbol on each side of
+    /// each bit decision.
+    pub fn dibit(&self, sample: f32) -> (f32, f32) {
+        let dist = |sym: f32| (sample - sym) * (sample - sym);
+        let llr = |zero: f32, one: f32| self.saturate((one - zero) / (2.0 * self.noise_var));
+
+        let msb = llr(dist(3.0).min(dist(1.0)), dist(-1.0).min(dist(-3.0)));
+        let lsb = llr(dist(1.0).min(dist(-1.0)), dist(3.0).min(dist(-3.0)));
+
+        (msb, lsb)
+    }
+
+    /// Quantize the given LLR to an integer soft bit, mapping the saturation limit to
+    /// full scale.
+    pub fn quantize(&self, llr: f32) -> i8 {
+        (self.saturate(llr) / self.limit * 127.0).round() as i8
+    }
+
+    /// Clamp the given LLR to the saturation limit.
+    fn saturate(&self, llr: f32) -> f32 {
+        llr.max(-self.limit).min(self.limit)
+    }
+}
+
+/// Decode the given 17 soft bits to the most likely codeword.
+///
+/// If there are exactly 17 soft bits, return `Some((data, cost))`, where `data` is the 9
+/// data bits and `cost` is the summed LLR magnitude of the bits that disagree with the
+/// chosen codeword. Otherwise, return `None`.
+///
+/// This is maximum-likelihood decoding, so it always chooses a codeword; use the cost to
+/// decide if the result is reliable enough.
+pub fn decode<S: SoftBits + ?Sized>(bits: &S) -> Option<(u16, i32)> {
+    if bits.len() != 17 {
+        return None;
+    }
+
+    if let Some(data) = clean(bits, 17, false) {
+        return Some((data, 0));
+    }
+
+    codewords().enumerate()
+        .map(|(data, word)| (data as u16, cost(bits, word)))
+        .min_by_key(|&(_, cost)| cost)
+}
+
+/// Decode the given 17 soft bits with the Chase-2 algorithm.
+///
+/// Rather than searching all 512 codewords, this flips every combination of the 2 least
+/// reliable bits of the hard decision and runs each of the 4 test words through the
+/// bounded-distance hard decoder, keeping the candidate with the least cost. This
+/// corrects up to 3 errors when two of them fall on the least reliable bits, and comes
+/// close to [`decode`](fn.decode.html) at a fraction of the work.
+///
+/// Return `Some((data, cost))` as for [`decode`](fn.decode.html), or `None` if there
+/// aren't exactly 17 soft bits or no test word could be decoded.
+pub fn decode_chase<S: SoftBits + ?Sized>(bits: &S) -> Option<(u16, i32)> {
+    if bits.len() != 17 {
+        return None;
+    }
+
+    let hard = (0..17).fold(0, |word, i| word << 1 | bits.hard(i) as u32);
+
+    let mut order = [0; 17];
+
+    for (i, pos) in order.iter_mut().enumerate() {
+        *pos = i;
+    }
+
+    order.sort_by_key(|&i| bits.llr(i).abs());
+
+    let (a, b) = (1 << (16 - order[0]), 1 << (16 - order[1]));
+
+    [0, a, b, a | b].iter()
+        .filter_map(|&flip| ::decode(hard ^ flip))
+        .map(|(data, _)| (data, cost(bits, encode(data))))
+        .min_by_key(|&(_, cost)| cost)
+}
+
+/// Decode the given 17 soft bits to the most likely codeword as in
+/// [`decode`](fn.decode.html), also reporting how far it was ahead of the runner-up.
+///
+/// The margin is the extra cost of the second most likely codeword, so it's large when
+/// the decision was clear and near zero when two codewords were almost equally likely.
+/// Voting and late-fusion stages can use it to weight this word's data against copies
+/// from other bursts. Return `None` unless there are exactly 17 soft bits.
+pub fn decode_confidence<S: SoftBits + ?Sized>(bits: &S) -> Option<SoftDecision> {
+    if bits.len() != 17 {
+        return None;
+    }
+
+    let (best, second) = codewords().enumerate()
+        .map(|(data, word)| (data as u16, cost(bits, word)))
+        .fold(((0, i32::MAX), i32::MAX), |(best, second), (data, cost)| {
+            if cost < best.1 {
+                ((data, cost), best.1)
+            } else {
+                (best, second.min(cost))
+            }
+        });
+
+    Some(SoftDecision {
+        data: best.0,
+        cost: best.1,
+        margin: second - best.1,
+    })
+}
+
+/// A soft decision along with its confidence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SoftDecision {
+    /// The 9 data bits of the most likely codeword.
+    pub data: u16,
+    /// Summed LLR magnitude of the bits that disagree with the chosen codeword.
+    pub cost: i32,
+    /// Cost of the second most likely codeword minus the chosen codeword's cost.
+    pub margin: i32,
+}
+
+/// Decode the given 18 soft bits of the extended (18, 9, 6) code, with the overall
+/// parity bit last, to the most likely codeword.
+///
+/// The parity bit's reliability takes part in the decoding metric like any other bit,
+/// rather than as a separate hard check, which realizes the extra distance of the
+/// extended code. The return value is as for [`decode`](fn.decode.html), but requires
+/// exactly 18 soft bits.
+pub fn decode_extended<S: SoftBits + ?Sized>(bits: &S) -> Option<(u16, i32)> {
+    if bits.len() != 18 {
+        return None;
+    }
+
+    if let Some(data) = clean(bits, 18, true) {
+        return Some((data, 0));
+    }
+
+    codewords().enumerate()
+        .map(|(data, word)| (data as u16, cost_bits(bits, extend(word), 18)))
+        .min_by_key(|&(_, cost)| cost)
+}
+
+/// Decode the given 16 soft bits of the DMR (16, 7, 6) quadrature residue code to the
+/// most likely codeword.
+///
+/// The two deleted MSB data bits are known to be zero, so only the 128 codewords of the
+/// shortened code are considered. If there are exactly 16 soft bits, return
+/// `Some((data, cost))`, where `data` is the 7 data bits. Otherwise, return `None`.
+pub fn decode_dmr<S: SoftBits + ?Sized>(bits: &S) -> Option<(u8, i32)> {
+    if bits.len() != 16 {
+        return None;
+    }
+
+    if let Some(data) = clean(bits, 16, true) {
+        return Some((data as u8, 0));
+    }
+
+    codewords().take(1 << 7).enumerate()
+        .map(|(data, word)| (data as u8, cost_bits(bits, extend(word), 16)))
+        .min_by_key(|&(_, cost)| cost)
+}
+
+/// Compute the summed LLR magnitude of the soft bits that disagree with the given
+/// codeword.
+pub fn cost<S: SoftBits + ?Sized>(bits: &S, word: u32) -> i32 {
+    cost_bits(bits, word, 17)
+}
+
+/// Get the data bits of the codeword formed by the hard decisions of the first `n` soft
+/// bits, with the overall parity bit last if `extended`, if there is one and none of the
+/// bits are erased.
+///
+/// Such a codeword costs nothing, and every other codeword disagrees with it on bits
+/// that each cost something, so it's the unique best choice and the search over all
+/// codewords can be skipped.
+fn clean<S: SoftBits + ?Sized>(bits: &S, n: usize, extended: bool) -> Option<u16> {
+    if (0..n).any(|i| bits.llr(i) == 0) {
+        return None;
+    }
+
+    let word = (0..n).fold(0, |word, i| word << 1 | bits.hard(i) as u32);
+    let base = if extended { word >> 1 } else { word };
+
+    if !is_codeword(base) || extended && extend(base) != word {
+        return None;
+    }
+
+    Some((base >> 8) as u16)
+}
+
+/// Compute the summed LLR magnitude of the first `n` soft bits that disagree with the
+/// given `n`-bit word.
+fn cost_bits<S: SoftBits + ?Sized>(bits: &S, word: u32, n: usize) -> i32 {
+    sum_cost((0..n).filter(|&i| bits.hard(i) != (word >> (n - 1 - i) & 1 == 1))
+        .map(|i| bits.llr(i)))
+}
+
+/// Sum the magnitudes of the given LLRs, saturating at `i32::MAX` rather than
+/// overflowing for large full-scale values.
+pub(crate) fn sum_cost<I: Iterator<Item = i32>>(llrs: I) -> i32 {
+    let sum = llrs.map(|llr| (llr as i64).abs()).sum::<i64>();
+    sum.min(i32::MAX as i64) as i32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sim::{RandomSource, SplitMix64};
+
+    fn to_llrs(word: u32, conf: i8) -> [i8; 17] {
+        let mut llrs = [0; 17];
+
+        for (i, llr) in llrs.iter_mut().enumerate() {
+            *llr = if word >> (16 - i) & 1 == 1 { -conf } else { conf };
+        }
+
+        llrs
+    }
+
+    #[test]
+    fn test_soft_bits() {
+        let llrs = [-128i8, 127, 0, 5, -5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+        assert_eq!(llrs.len(), 17);
+        assert_eq!(llrs.full_scale(), 127);
+        assert_eq!(llrs.llr(0), -127);
+        assert_eq!(llrs.llr(1), 127);
+        assert!(llrs.hard(0));
+        assert!(!llrs.hard(2));
+        assert!(llrs.hard(4));
+
+        let s = Scaled::new(&[300, -300, 20], 100);
+        assert_eq!(s.len(), 3);
+        assert_eq!(s.llr(0), 100);
+        assert_eq!(s.llr(1), -100);
+        assert_eq!(s.llr(2), 20);
+
+        // Costs of large-scale LLRs saturate instead of overflowing.
+        let big = [i32::MAX; 18];
+        let s = Scaled::new(&big, i32::MAX);
+        assert_eq!(cost(&s, 0x1ffff), i32::MAX);
+        assert_eq!(cost(&s, 0), 0);
+        assert_eq!(decode_extended(&Scaled::new(&[-(1 << 30); 18], i32::MAX)), Some((0x1ff, 0)));
+        assert_eq!(sum_cost([i32::MIN + 1, i32::MAX, 5].iter().cloned()), i32::MAX);
+    }
+
+    #[test]
+    fn test_soft_bits_float() {
+        let mut llrs = [0.0f32; 17];
+        llrs[0] = 1.5;
+        llrs[1] = -1000.0;
+        llrs[2] = f32::NAN;
+        llrs[3] = f32::INFINITY;
+
+        assert_eq!(llrs.len(), 17);
+        assert_eq!(llrs.full_scale(), 65536);
+        assert_eq!(llrs.llr(0), 1536);
+        assert_eq!(llrs.llr(1), -65536);
+        assert_eq!(llrs.llr(2), 0);
+        assert_eq!(llrs.llr(3), 65536);
+        assert!(llrs.hard(1));
+
+        let w = encode(0b111100001);
+        let mut llrs = [0.0f32; 17];
+
+        for (i, llr) in llrs.iter_mut().enumerate() {
+            *llr = if w >> (16 - i) & 1 == 1 { -4.0 } else { 4.0 };
+        }
+
+        llrs[2] = -llrs[2] / 8.0;
+        llrs[9] = -llrs[9] / 8.0;
+        llrs[13] = -llrs[13] / 8.0;
+
+        assert_eq!(decode(&llrs), Some((0b111100001, 1536)));
+        assert_eq!(decode(&llrs[..]), Some((0b111100001, 1536)));
+    }
+
+    #[test]
+    fn test_decode_extended() {
+        let to_llrs = |word: u32, n: usize| {
+            (0..n).map(|i| if word >> (n - 1 - i) & 1 == 1 { -100 } else { 100 })
+                .collect::<Vec<i16>>()
+        };
+
+        for d in 0..1 << 9 {
+            assert_eq!(decode_extended(&to_llrs(extend(encode(d)), 18)[..]), Some((d, 0)));
+        }
+
+        // Two codewords at distance 5, with the received bits leaning slightly toward
+        // the wrong one.
+        let c = encode(0b000110110);
+        let m = ::codewords().find(|w| w.count_ones() == 5).unwrap();
+
+        let mut llrs = to_llrs(extend(c), 18);
+        let diffs = (0..17).filter(|&i| m >> (16 - i) & 1 == 1).collect::<Vec<_>>();
+
+        for (n, &i) in diffs.iter().enumerate() {
+            llrs[i] = if n < 3 { -llrs[i] / 10 } else { llrs[i] / 10 };
+        }
+
+        assert_eq!(decode(&llrs[..17]).map(|(d, _)| d), Some(0b000110110 ^ (m >> 8) as u16));
+
+        // The unaffected parity bit tips the extended decoder to the right codeword.
+        assert_eq!(decode_extended(&llrs[..]), Some((0b000110110, 30)));
+
+        assert_eq!(decode_extended(&llrs[..17]), None);
+    }
+
+    #[test]
+    fn test_decode_dmr() {
+        let to_llrs = |word: u32| {
+            (0..16).map(|i| if word >> (15 - i) & 1 == 1 { -100i16 } else { 100 })
+                .collect::<Vec<_>>()
+        };
+
+        for d in 0..1 << 7 {
+            let llrs = to_llrs(extend(encode(d)));
+            assert_eq!(decode_dmr(&llrs[..]), Some((d as u8, 0)));
+
+            // Three erasures and one error are within the distance of 6.
+            let mut llrs = llrs.clone();
+            llrs[0] = 0;
+            llrs[5] = 0;
+            llrs[10] = 0;
+            llrs[15] = -llrs[15] / 2;
+            assert_eq!(decode_dmr(&llrs[..]), Some((d as u8, 50)));
+        }
+
+        assert_eq!(decode_dmr(&[0i8; 17]), None);
+    }
+
+    #[test]
+    fn test_llr_converter() {
+        let c = LlrConverter::new(0.5);
+        assert_eq!(c.binary(1.0), 4.0);
+        assert_eq!(c.binary(-0.5), -2.0);
+        assert_eq!(c.binary(100.0), 16.0);
+        assert_eq!(c.with_limit(3.0).binary(-1.0), -3.0);
+
+        assert_eq!(c.quantize(16.0), 127);
+        assert_eq!(c.quantize(-8.0), -64);
+        assert_eq!(c.quantize(-1000.0), -127);
+        assert_eq!(c.quantize(0.0), 0);
+
+        let (msb, lsb) = c.dibit(3.0);
+        assert!(msb > 0.0 && lsb < 0.0);
+        let (msb, lsb) = c.dibit(1.0);
+        assert!(msb > 0.0 && lsb > 0.0);
+        let (msb, lsb) = c.dibit(-1.0);
+        assert!(msb < 0.0 && lsb > 0.0);
+        let (msb, lsb) = c.dibit(-3.0);
+        assert!(msb < 0.0 && lsb < 0.0);
+
+        // Samples on a decision boundary carry no information about that bit.
+        assert_eq!(c.dibit(0.0).0, 0.0);
+        assert_eq!(c.dibit(2.0).1, 0.0);
+
+        // Lower noise gives more confident LLRs.
+        let quiet = LlrConverter::from_snr_db(20.0, 1.0);
+        let noisy = LlrConverter::from_snr_db(0.0, 1.0);
+        assert!(quiet.binary(0.1) > noisy.binary(0.1));
+        assert_eq!(noisy.binary(0.25), 0.5);
+    }
+
+    #[test]
+    fn test_decode() {
+        for d in 0..1 << 9 {
+            assert_eq!(decode(&to_llrs(encode(d), 100)), Some((d, 0)));
+        }
+
+        let w = encode(0b100110101);
+
+        // Three weak errors are beyond the hard decoder but not the soft one.
+        let mut llrs = to_llrs(w, 100);
+        llrs[0] = -llrs[0] / 10;
+        llrs[7] = -llrs[7] / 10;
+        llrs[16] = -llrs[16] / 10;
+        assert_eq!(decode(&llrs), Some((0b100110101, 30)));
+
+        // Erasures carry no cost.
+        let mut llrs = to_llrs(w, 100);
+        for i in 0..4 {
+            llrs[i * 4] = 0;
+        }
+        assert_eq!(decode(&llrs), Some((0b100110101, 0)));
+
+        let llrs = [10i16; 17];
+        assert_eq!(decode(&llrs), Some((0, 0)));
+
+        assert_eq!(decode(&[1i8; 16][..]), None);
+    }
+
+    #[test]
+    fn test_decode_chase() {
+        for d in 0..1 << 9 {
+            assert_eq!(decode_chase(&to_llrs(encode(d), 100)), Some((d, 0)));
+        }
+
+        let w = encode(0b110001011);
+
+        // Three errors, two on the least reliable bits.
+        let mut llrs = to_llrs(w, 100);
+        llrs[2] = -llrs[2];
+        llrs[8] = -llrs[8] / 20;
+        llrs[13] = -llrs[13] / 10;
+
+        let hard = (0..17).fold(0, |w, i| w << 1 | llrs.hard(i) as u32);
+        assert_ne!(::decode(hard).map(|(d, _)| d), Some(0b110001011));
+        assert_eq!(decode_chase(&llrs), Some((0b110001011, 115)));
+        assert_eq!(decode_chase(&llrs), decode(&llrs));
+
+        // Chase agrees with the full search on mildly noisy words.
+        let mut rng = SplitMix64::new(17);
+
+        for _ in 0..500 {
+            let d = rng.next_below(1 << 9) as u16;
+            let mut llrs = to_llrs(encode(d), 60);
+
+            for _ in 0..2 {
+                let i = rng.next_below(17) as usize;
+                llrs[i] = -llrs[i] / (1 + rng.next_below(8) as i8);
+            }
+
+            assert_eq!(decode_chase(&llrs), decode(&llrs));
+        }
+
+        assert_eq!(decode_chase(&[1i8; 18][..]), None);
+    }
+
+    #[test]
+    fn test_decode_confidence() {
+        let w = encode(0b001101011);
+
+        // A clean word is at least 5 bits away from every other codeword.
+        let d = decode_confidence(&to_llrs(w, 100)).unwrap();
+        assert_eq!(d, SoftDecision { data: 0b001101011, cost: 0, margin: 500 });
+
+        // Errors raise the cost and eat into the margin.
+        let mut llrs = to_llrs(w, 100);
+        llrs[3] = -llrs[3];
+        llrs[9] = -llrs[9] / 4;
+
+        let d = decode_confidence(&llrs).unwrap();
+        assert_eq!((d.data, d.cost), (0b001101011, 125));
+        assert!(d.margin > 0 && d.margin < 500);
+        assert_eq!(decode(&llrs), Some((d.data, d.cost)));
+
+        // With no information at all, every codeword ties.
+        let d = decode_confidence(&[0i8; 17]).unwrap();
+        assert_eq!((d.cost, d.margin), (0, 0));
+
+        // Halfway between two codewords, neither is preferred.
+        let m = ::codewords().find(|w| w.count_ones() == 5).unwrap();
+        let mut llrs = to_llrs(0, 100);
+        for i in (0..17).filter(|&i| m >> (16 - i) & 1 == 1) {
+            llrs[i] = 0;
+        }
+        assert_eq!(decode_confidence(&llrs).map(|d| d.margin), Some(0));
+
+        assert_eq!(decode_confidence(&[1i8; 16][..]), None);
+    }
+
+    #[test]
+    fn test_clean() {
+        let w = encode(0b010011101);
+        assert_eq!(clean(&to_llrs(w, 100), 17, false), Some(0b010011101));
+        assert_eq!(clean(&to_llrs(w ^ 0b100, 100), 17, false), None);
+
+        let mut llrs = to_llrs(w, 100);
+        llrs[5] = 0;
+        assert_eq!(clean(&llrs, 17, false), None);
+
+        let to_bits = |word: u32, n: usize| {
+            (0..n).map(|i| if word >> (n - 1 - i) & 1 == 1 { -9i8 } else { 9 })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(clean(&to_bits(extend(w), 18)[..], 18, true), Some(0b010011101));
+        assert_eq!(clean(&to_bits(extend(w) ^ 1, 18)[..], 18, true), None);
+        assert_eq!(clean(&to_bits(extend(encode(0b1011001)), 16)[..], 16, true),
+                   Some(0b1011001));
+
+        // The shortcut agrees with the full search whenever it applies.
+        for (data, word) in ::codewords().enumerate() {
+            let bits = to_llrs(word, 3);
+            let full = codewords().map(|w| cost(&bits, w)).enumerate()
+                .min_by_key(|&(_, cost)| cost);
+
+            assert_eq!(full, Some((data, 0)));
+            assert_eq!(decode(&bits), Some((data as u16, 0)));
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Running counts of decode outcomes.
+//!
+//! Receivers usually track how many words arrived clean, how many needed 1 or 2
+//! corrections, and how many couldn't be decoded, both for link quality displays and for
+//! spotting a degrading channel. [`ErrorStats`](struct.ErrorStats.html) keeps these
+//! counts, either by wrapping the decoder or by recording results from elsewhere, and
+//! counts from separate threads or slots can be merged.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::stats::ErrorStats;
+//!
+//! let w = cai_cyclic::encode(0b100011101);
+//! let mut stats = ErrorStats::new();
+//!
+//! assert_eq!(stats.decode(w), Some((0b100011101, 0)));
+//! assert_eq!(stats.decode(w ^ 0b1), Some((0b100011101, 1)));
+//! stats.record(cai_cyclic::decode_p25(0xffff));
+//!
+//! assert_eq!(stats.total(), 3);
+//! assert_eq!((stats.clean(), stats.corrected(1), stats.failures()), (1, 1, 1));
+//! ```
+
+/// Counts of decode outcomes by number of corrected errors.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ErrorStats {
+    /// Words decoded with no corrections.
+    clean: u64,
+    /// Words decoded with 1 and with 2 corrections.
+    corrected: [u64; 2],
+    /// Words that couldn't be decoded.
+    failures: u64,
+}
+
+impl ErrorStats {
+    /// Create a new, empty accumulator.
+    pub fn new() -> Self { Self::default() }
+
+    /// Decode the given 17-bit word as in [`decode`](../fn.decode.html) and record the
+    /// outcome.
+    pub fn decode(&mut self, word: u32) -> Option<(u16, usize)> {
+        let result = ::decode(word);
+        self.record(result);
+        result
+    }
+
+    /// Record the result of a decoder returning `Option<(data, err)>` with up to 2
+    /// corrected errors, such as any of the crate's decoders for the (17, 9, 5) code and
+    /// its variants.
+    pub fn record<T>(&mut self, result: Option<(T, usize)>) {
+        match result {
+            Some((_, 0)) => self.clean += 1,
+            Some((_, err)) => {
+                assert!(err <= 2);
+                self.corrected[err - 1] += 1;
+            },
+            None => self.failures += 1,
+        }
+    }
+
+    /// Number of words decoded with no corrections.
+    pub fn clean(&self) -> u64 { self.clean }
+
+    /// Number of words decoded with the given number of corrections, 1 or 2.
+    pub fn corrected(&self, errors: usize) -> u64 {
+        assert!(errors == 1 || errors == 2);
+        self.corrected[errors - 1]
+    }
+
+    /// Number of words that couldn't be decoded.
+    pub fn failures(&self) -> u64 { self.failures }
+
+    /// Total number of words recorded.
+    pub fn total(&self) -> u64 {
+        self.clean + self.corrected[0] + self.corrected[1] + self.failures
+    }
+
+    /// Total number of bits corrected over all decoded words.
+    pub fn corrected_bits(&self) -> u64 { self.corrected[0] + 2 * self.corrected[1] }
+
+    /// Add the counts from another accumulator into this one.
+    pub fn merge(&mut self, other: &ErrorStats) {
+        self.clean += other.clean;
+        self.corrected[0] += other.corrected[0];
+        self.corrected[1] += other.corrected[1];
+        self.failures += other.failures;
+    }
+
+    /// Clear all counts.
+    pub fn reset(&mut self) { *self = Self::default() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use encode;
+
+    #[test]
+    fn test_stats() {
+        let w = encode(0b011110000);
+        let mut s = ErrorStats::new();
+        assert_eq!(s.total(), 0);
+
+        assert_eq!(s.decode(w), Some((0b011110000, 0)));
+        assert_eq!(s.decode(w ^ 1 << 16), Some((0b011110000, 1)));
+        assert_eq!(s.decode(w ^ 0b11), Some((0b011110000, 2)));
+        assert_eq!(s.decode(w ^ 0b101 << 5), Some((0b011110000, 2)));
+        s.record(None::<(u16, usize)>);
+
+        assert_eq!((s.clean(), s.corrected(1), s.corrected(2), s.failures()), (1, 1, 2, 1));
+        assert_eq!(s.total(), 5);
+        assert_eq!(s.corrected_bits(), 5);
+
+        let mut t = ErrorStats::new();
+        t.record(::decode_dmr(::encode_dmr(5)));
+        t.record(::decode_extended(0b1110));
+        t.merge(&s);
+
+        assert_eq!((t.clean(), t.failures(), t.total()), (2, 1 + s.failures(), 7));
+
+        t.reset();
+        assert_eq!(t, ErrorStats::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_corrected_range() {
+        ErrorStats::new().corrected(3);
+    }
+}
@@ -0,0 +1,122 @@
+//! Complete lookup table decoder.
+//!
+//! The table maps every possible 17-bit word directly to its decoded result, so decoding
+//! becomes a single memory access. It takes 256 KiB and is built at runtime in a few
+//! milliseconds, which suits offline and desktop processing of large captures.
+//!
+//! With this module enabled, [`decode`](../fn.decode.html) uses a shared table from
+//! [`shared`](fn.shared.html), so existing callers get the speedup without changes.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::table::DecodeTable;
+//!
+//! let table = DecodeTable::new();
+//! let w = cai_cyclic::encode(0b101101101);
+//!
+//! assert_eq!(table.decode(w ^ 0b1001), Some((0b101101101, 2)));
+//! assert_eq!(table.decode(w ^ 0b1001), cai_cyclic::decode(w ^ 0b1001));
+//! ```
+
+use std::sync::OnceLock;
+
+use codewords;
+
+/// Flag marking a valid table entry.
+const VALID: u16 = 1 << 15;
+
+/// Lookup table covering every 17-bit word.
+#[derive(Clone)]
+pub struct DecodeTable {
+    /// Entry for each word, holding the valid flag in the MSB, the number of corrected
+    /// errors in bits 9 and 10, and the data in the 9 LSBs.
+    entries: Box<[u16]>,
+}
+
+impl DecodeTable {
+    /// Build the table.
+    pub fn new() -> Self {
+        let mut entries = vec![0; 1 << 17].into_boxed_slice();
+
+        // Every word within distance 2 of a codeword decodes to that codeword, and every
+        // other word is uncorrectable.
+        for (data, word) in codewords().enumerate() {
+            for i in 0..17 {
+                for j in i..17 {
+                    let (err, pat) = if i == j { (1, 1 << i) } else { (2, 1 << i | 1 << j) };
+                    entries[(word ^ pat) as usize] = VALID | err << 9 | data as u16;
+                }
+            }
+
+            entries[word as usize] = VALID | data as u16;
+        }
+
+        DecodeTable { entries }
+    }
+
+    /// Try to decode the given 17-bit word, with the same results as
+    /// [`decode`](../fn.decode.html).
+    ///
+    /// With the `cross-check` feature, the result is also compared against the scalar
+    /// decoder, panicking with the word, both results, and the raw entry if they differ.
+    pub fn decode(&self, word: u32) -> Option<(u16, usize)> {
+        assert_eq!(word >> 17, 0);
+
+        let entry = self.entries[word as usize];
+
+        let result = match entry {
+            e if e & VALID == 0 => None,
+            e => Some((e & 0x1ff, (e >> 9 & 0b11) as usize)),
+        };
+
+        #[cfg(feature = "cross-check")]
+        ::cross_check("table", word, result, format_args!("entry {:#06x}", entry));
+
+        result
+    }
+}
+
+impl Default for DecodeTable {
+    fn default() -> Self { Self::new() }
+}
+
+/// Get the process-wide table, building it on the first call.
+pub fn shared() -> &'static DecodeTable {
+    static TABLE: OnceLock<DecodeTable> = OnceLock::new();
+    TABLE.get_or_init(DecodeTable::new)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use reference;
+
+    #[test]
+    fn test_decode() {
+        let t = DecodeTable::new();
+
+        assert_eq!(t.decode(0), Some((0, 0)));
+        assert_eq!(t.decode(0b11), Some((0, 2)));
+
+        assert_eq!(reference::check_words(|w| t.decode(w), (0..1 << 17).step_by(7)), Ok(()));
+    }
+
+    #[test]
+    fn test_shared() {
+        assert!(std::ptr::eq(shared(), shared()));
+
+        for w in 0..1 << 17 {
+            assert_eq!(::decode(w), ::decode_diagnosed(w).ok());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cross-check")]
+    #[should_panic(expected = "table decoder diverged for word 0x00003")]
+    fn test_cross_check() {
+        let mut t = DecodeTable::new();
+        t.entries[0b11] = VALID | 1;
+        t.decode(0b11);
+    }
+}
@@ -0,0 +1,176 @@
+//! Encoding and decoding of the link control carried by P25 TDULC frames.
+//!
+//! The terminator data unit with link control (TDULC) ends a call with a final copy of
+//! the 72-bit link control word. The word is split into 12 hexbits, MSB first, and
+//! protected by the (24, 12, 13) [Reed-Solomon](../reed_solomon/index.html) code. Each
+//! pair of the resulting 24 hexbits then forms the 12 data bits of an extended (24, 12,
+//! 8) [Golay](../golay/index.html) codeword, with the first hexbit in the high bits,
+//! giving 12 Golay codewords and 288 bits in all.
+//!
+//! Decoding first corrects up to 3 errors in each Golay codeword. A Golay codeword with
+//! 4 detected errors doesn't give up the whole word, but instead marks its 2 hexbits as
+//! erasures for the Reed-Solomon decoder, so up to 6 such codewords can be recovered.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::tdulc;
+//!
+//! let lc = [0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x56, 0x78];
+//! let mut words = tdulc::encode(&lc);
+//!
+//! // Too many errors for the Golay code alone.
+//! words[3] ^= 0b1111;
+//! words[7] ^= 0b111;
+//!
+//! assert_eq!(tdulc::decode(&words), Some((lc, 7)));
+//! ```
+
+use {golay, reed_solomon};
+
+/// Encode the given 9-byte link control word into 12 extended Golay codewords, in
+/// transmission order.
+pub fn encode(lc: &[u8; 9]) -> [u32; 12] {
+    let syms = reed_solomon::encode_24_12(&to_hexbits(lc));
+    let mut words = [0; 12];
+
+    for (w, pair) in words.iter_mut().zip(syms.chunks(2)) {
+        *w = golay::encode_extended((pair[0] as u16) << 6 | pair[1] as u16);
+    }
+
+    words
+}
+
+/// Try to decode the given 12 received extended Golay words.
+///
+/// If decoding was successful, return `Some((lc, err))`, where `lc` is the 9-byte link
+/// control word and `err` is the number of bits that differ from the corrected
+/// codewords. Otherwise, return `None` to indicate an unrecoverable error.
+pub fn decode(words: &[u32; 12]) -> Option<([u8; 9], usize)> {
+    let mut syms = [0; 24];
+    let mut erasures = [0; 24];
+    let mut erased = 0;
+
+    for (i, &w) in words.iter().enumerate() {
+        match golay::decode_extended(w) {
+            Some((data, _)) => {
+                syms[i * 2] = (data >> 6) as u8;
+                syms[i * 2 + 1] = (data & 0x3f) as u8;
+            },
+            None => {
+                erasures[erased] = i * 2;
+                erasures[erased + 1] = i * 2 + 1;
+                erased += 2;
+            },
+        }
+    }
+
+    let (data, _) = reed_solomon::decode_24_12(&syms, &erasures[..erased])?;
+    let lc = from_hexbits(&data);
+
+    let err = encode(&lc).iter().zip(words)
+        .map(|(&w, &r)| (w ^ r).count_ones() as usize)
+        .sum();
+
+    Some((lc, err))
+}
+
+/// Split the given 72 bits into 12 hexbits, MSB first.
+fn to_hexbits(bytes: &[u8; 9]) -> [u8; 12] {
+    let mut syms = [0; 12];
+
+    for (s, b) in syms.chunks_mut(4).zip(bytes.chunks(3)) {
+        let bits = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for (i, s) in s.iter_mut().enumerate() {
+            *s = (bits >> (18 - i * 6) & 0x3f) as u8;
+        }
+    }
+
+    syms
+}
+
+/// Join the given 12 hexbits into 72 bits, MSB first.
+fn from_hexbits(syms: &[u8; 12]) -> [u8; 9] {
+    let mut bytes = [0; 9];
+
+    for (b, s) in bytes.chunks_mut(3).zip(syms.chunks(4)) {
+        let bits = s.iter().fold(0, |acc, &s| acc << 6 | s as u32);
+
+        b[0] = (bits >> 16) as u8;
+        b[1] = (bits >> 8) as u8;
+        b[2] = bits as u8;
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {golay, reed_solomon};
+
+    const LC: [u8; 9] = [0x00, 0x90, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde];
+
+    #[test]
+    fn test_hexbits() {
+        let syms = to_hexbits(&LC);
+        assert_eq!(&syms[..4], &[0b000000, 0b001001, 0b000000, 0b010010]);
+        assert_eq!(from_hexbits(&syms), LC);
+    }
+
+    #[test]
+    fn test_layout() {
+        let words = encode(&LC);
+        let syms = reed_solomon::encode_24_12(&to_hexbits(&LC));
+
+        for (i, &w) in words.iter().enumerate() {
+            let data = (syms[i * 2] as u16) << 6 | syms[i * 2 + 1] as u16;
+            assert_eq!(w, golay::encode_extended(data));
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let words = encode(&LC);
+        assert_eq!(decode(&words), Some((LC, 0)));
+
+        // Three errors in every Golay word.
+        let mut w = words;
+
+        for (i, w) in w.iter_mut().enumerate() {
+            *w ^= 0b10101 << i;
+        }
+
+        assert_eq!(decode(&w), Some((LC, 36)));
+
+        // Six Golay words with detected errors are erased and recovered.
+        let mut w = words;
+
+        for w in &mut w[..6] {
+            *w ^= 0xf00;
+        }
+
+        assert_eq!(decode(&w), Some((LC, 24)));
+
+        // A miscorrected Golay word costs 2 symbol errors, leaving room for 4 erased
+        // Golay words.
+        let mut w = words;
+        w[11] ^= 0b111110000000;
+
+        for w in &mut w[..4] {
+            *w ^= 0xf;
+        }
+
+        assert_eq!(decode(&w), Some((LC, 21)));
+
+        // Seven erased Golay words are too many.
+        let mut w = words;
+
+        for w in &mut w[..7] {
+            *w ^= 0xf;
+        }
+
+        assert_eq!(decode(&w), None);
+    }
+}
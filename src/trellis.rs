@@ -0,0 +1,634 @@
+//! Encoding and decoding of the trellis codes used by DMR and P25 data.
+//!
+//! Both codes split the payload into input symbols, followed by a zero symbol to flush
+//! the encoder. Each symbol, together with the encoder state, selects one of 16
+//! constellation points, and the state is then set to the symbol itself. Each point is
+//! sent as two dibits, giving 98 dibits or 196 bits per block.
+//!
+//! - The rate 3/4 code takes a 144-bit payload as 48 tribits, over an 8-state trellis.
+//!   It carries DMR rate 3/4 data bursts and P25 confirmed packet data, and is handled
+//!   by [`encode_dmr`](fn.encode_dmr.html) and [`encode_p25_3_4`](fn.encode_p25_3_4.html).
+//! - The rate 1/2 code takes a 96-bit payload as 48 dibits, over a 4-state trellis. It
+//!   carries P25 TSBKs and unconfirmed packet data, and is handled by
+//!   [`encode_p25_1_2`](fn.encode_p25_1_2.html).
+//!
+//! [`Trellis34`](struct.Trellis34.html) and [`Trellis12`](struct.Trellis12.html) provide
+//! the generic [`code`](../code/index.html) traits for the two codes.
+//!
+//! The 49 points are interleaved before transmission with the schedule shared by both
+//! standards, so the points at index `4q + r`, for each remainder `r`, are sent together
+//! as a block, starting with `r = 0`. Adjacent errors on the air therefore land on points
+//! far apart in the trellis.
+//!
+//! Decoding uses the Viterbi algorithm, choosing the payload whose encoding has the best
+//! correlation with the received bits. It can take hard bits, hard dibits through
+//! [`from_dibits`](fn.from_dibits.html), or soft bits through the
+//! [`SoftBits`](../soft/trait.SoftBits.html) trait, such as the per-dibit LLRs from
+//! [`LlrConverter::dibit`](../soft/struct.LlrConverter.html#method.dibit). The codes are
+//! designed around the distance between symbol levels rather than bits, and two rate 3/4
+//! paths can differ in as few as 3 bits, so hard decoding only corrects isolated errors
+//! and soft bits recover far more.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::trellis;
+//!
+//! let mut payload = [0; 18];
+//! payload[..4].copy_from_slice(b"DMR!");
+//!
+//! let mut bits = trellis::encode_dmr(&payload);
+//!
+//! for &i in &[3, 50, 51, 120, 190] {
+//!     bits[i] = !bits[i];
+//! }
+//!
+//! assert_eq!(trellis::decode_dmr(&bits), (payload, 5));
+//!
+//! let tsbk = [0x07, 0x00, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x5a, 0x3c];
+//! let mut bits = trellis::encode_p25_1_2(&tsbk);
+//!
+//! for i in (10..196).step_by(20) {
+//!     bits[i] = !bits[i];
+//! }
+//!
+//! assert_eq!(trellis::decode_p25_1_2(&bits), (tsbk, 10));
+//! ```
+
+use code::{Decoder, DynCode, Encoder, from_symbols, to_symbols};
+use soft::{SoftBits, sum_cost};
+
+/// Encode the given 144-bit payload with the rate 3/4 code, with the first transmitted
+/// bit in the MSB of the first byte, into 196 interleaved bits in transmission order.
+pub fn encode_dmr(data: &[u8; 18]) -> [bool; 196] {
+    RATE_3_4.encode(data)
+}
+
+/// Decode the given 196 interleaved hard bits of the rate 3/4 code in transmission
+/// order to the most likely payload.
+///
+/// Return `(data, err)`, where `data` is the 144-bit payload and `err` is the number of
+/// received bits that differ from its encoding. Every word decodes, so the result should
+/// be checked with the burst's CRC.
+pub fn decode_dmr(bits: &[bool; 196]) -> ([u8; 18], usize) {
+    RATE_3_4.decode_hard(bits)
+}
+
+/// Decode the given 196 interleaved soft bits of the rate 3/4 code in transmission order
+/// to the most likely payload.
+///
+/// If there are exactly 196 soft bits, return `Some((data, cost))`, where `data` is the
+/// 144-bit payload and `cost` is the summed LLR magnitude of the bits that disagree with
+/// its encoding. Otherwise, return `None`.
+pub fn decode_dmr_soft<S: SoftBits + ?Sized>(bits: &S) -> Option<([u8; 18], i32)> {
+    RATE_3_4.decode_soft(bits)
+}
+
+/// Encode the given 144-bit P25 payload with the rate 3/4 code.
+///
+/// This is the same code as DMR's, so it's equivalent to
+/// [`encode_dmr`](fn.encode_dmr.html).
+pub fn encode_p25_3_4(data: &[u8; 18]) -> [bool; 196] { encode_dmr(data) }
+
+/// Decode the given 196 interleaved hard bits of the P25 rate 3/4 code, as in
+/// [`decode_dmr`](fn.decode_dmr.html).
+pub fn decode_p25_3_4(bits: &[bool; 196]) -> ([u8; 18], usize) { decode_dmr(bits) }
+
+/// Decode the given 196 interleaved soft bits of the P25 rate 3/4 code, as in
+/// [`decode_dmr_soft`](fn.decode_dmr_soft.html).
+pub fn decode_p25_3_4_soft<S: SoftBits + ?Sized>(bits: &S) -> Option<([u8; 18], i32)> {
+    decode_dmr_soft(bits)
+}
+
+/// Encode the given 96-bit payload with the P25 rate 1/2 code, with the first
+/// transmitted bit in the MSB of the first byte, into 196 interleaved bits in
+/// transmission order.
+pub fn encode_p25_1_2(data: &[u8; 12]) -> [bool; 196] {
+    RATE_1_2.encode(data)
+}
+
+/// Decode the given 196 interleaved hard bits of the P25 rate 1/2 code in transmission
+/// order to the most likely payload.
+///
+/// Return `(data, err)`, where `data` is the 96-bit payload and `err` is the number of
+/// received bits that differ from its encoding. Every word decodes, so the result should
+/// be checked with the block's CRC.
+pub fn decode_p25_1_2(bits: &[bool; 196]) -> ([u8; 12], usize) {
+    RATE_1_2.decode_hard(bits)
+}
+
+/// Decode the given 196 interleaved soft bits of the P25 rate 1/2 code in transmission
+/// order to the most likely payload.
+///
+/// If there are exactly 196 soft bits, return `Some((data, cost))`, where `data` is the
+/// 96-bit payload and `cost` is the summed LLR magnitude of the bits that disagree with
+/// its encoding. Otherwise, return `None`.
+pub fn decode_p25_1_2_soft<S: SoftBits + ?Sized>(bits: &S) -> Option<([u8; 12], i32)> {
+    RATE_1_2.decode_soft(bits)
+}
+
+/// The rate 3/4 code, for use with the generic [`code`](../code/index.html) traits.
+///
+/// Decoding always succeeds, with errors counted as in
+/// [`decode_dmr`](fn.decode_dmr.html). The code has no fixed minimum distance between
+/// blocks, so it doesn't implement `BlockCode`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trellis34;
+
+impl Encoder for Trellis34 {
+    type Data = [u8; 18];
+    type Codeword = [bool; 196];
+
+    fn encode(&self, data: [u8; 18]) -> [bool; 196] { encode_dmr(&data) }
+}
+
+impl Decoder for Trellis34 {
+    fn decode(&self, word: [bool; 196]) -> Option<([u8; 18], usize)> {
+        Some(decode_dmr(&word))
+    }
+}
+
+impl DynCode for Trellis34 {
+    fn name(&self) -> &'static str { "trellis34" }
+
+    fn data_len(&self) -> Option<usize> { Some(144) }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+        if data.len() != 144 {
+            return None;
+        }
+
+        let mut bytes = [0; 18];
+        to_symbols(data, 8, &mut bytes);
+
+        Some(encode_dmr(&bytes).to_vec())
+    }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        let (data, err) = decode_dmr(&to_block(word)?);
+        Some((from_symbols(&data, 8), err))
+    }
+}
+
+/// The P25 rate 1/2 code, for use with the generic [`code`](../code/index.html) traits,
+/// as for [`Trellis34`](struct.Trellis34.html).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trellis12;
+
+impl Encoder for Trellis12 {
+    type Data = [u8; 12];
+    type Codeword = [bool; 196];
+
+    fn encode(&self, data: [u8; 12]) -> [bool; 196] { encode_p25_1_2(&data) }
+}
+
+impl Decoder for Trellis12 {
+    fn decode(&self, word: [bool; 196]) -> Option<([u8; 12], usize)> {
+        Some(decode_p25_1_2(&word))
+    }
+}
+
+impl DynCode for Trellis12 {
+    fn name(&self) -> &'static str { "trellis12" }
+
+    fn data_len(&self) -> Option<usize> { Some(96) }
+
+    fn encode(&self, data: &[bool]) -> Option<Vec<bool>> {
+        if data.len() != 96 {
+            return None;
+        }
+
+        let mut bytes = [0; 12];
+        to_symbols(data, 8, &mut bytes);
+
+        Some(encode_p25_1_2(&bytes).to_vec())
+    }
+
+    fn decode(&self, word: &[bool]) -> Option<(Vec<bool>, usize)> {
+        let (data, err) = decode_p25_1_2(&to_block(word)?);
+        Some((from_symbols(&data, 8), err))
+    }
+}
+
+/// Copy the given received bits into a block, or return `None` if there are the wrong
+/// number of them.
+fn to_block(word: &[bool]) -> Option<[bool; 196]> {
+    if word.len() != 196 {
+        return None;
+    }
+
+    let mut bits = [false; 196];
+    bits.copy_from_slice(word);
+
+    Some(bits)
+}
+
+/// Split the given 98 hard dibits, each in the low 2 bits of a byte with the first
+/// dibit first, into 196 bits in transmission order.
+pub fn from_dibits(dibits: &[u8; 98]) -> [bool; 196] {
+    let mut bits = [false; 196];
+
+    for (pair, &d) in bits.chunks_mut(2).zip(dibits.iter()) {
+        assert_eq!(d >> 2, 0);
+
+        pair[0] = d & 0b10 != 0;
+        pair[1] = d & 0b01 != 0;
+    }
+
+    bits
+}
+
+/// Number of constellation points per block, including the flushing point.
+const POINTS: usize = 49;
+
+/// Dibit pair sent for each constellation point, with the first dibit in the high 2
+/// bits, using the DMR/P25 mapping of 01 → +3, 00 → +1, 10 → -1, 11 → -3.
+const DIBITS: [u8; 16] = [
+    0b0010, 0b1010, 0b0111, 0b1111, 0b1110, 0b0110, 0b1011, 0b0011,
+    0b1101, 0b0101, 0b1000, 0b0000, 0b0001, 0b1001, 0b0100, 0b1100,
+];
+
+/// The rate 3/4 code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RATE_3_4: Trellis = Trellis {
+    bits: 3,
+    points: &[
+        0, 8, 4, 12, 2, 10, 6, 14,
+        4, 12, 2, 10, 6, 14, 0, 8,
+        1, 9, 5, 13, 3, 11, 7, 15,
+        5, 13, 3, 11, 7, 15, 1, 9,
+        3, 11, 7, 15, 1, 9, 5, 13,
+        7, 15, 1, 9, 5, 13, 3, 11,
+        2, 10, 6, 14, 0, 8, 4, 12,
+        6, 14, 0, 8, 4, 12, 2, 10,
+    ],
+};
+
+/// The P25 rate 1/2 code.
+#[cfg_attr(cai_cyclic_link_section, link_section = env!("CAI_CYCLIC_LINK_SECTION"))]
+static RATE_1_2: Trellis = Trellis {
+    bits: 2,
+    points: &[
+        0, 15, 12, 3,
+        4, 11, 8, 7,
+        13, 2, 1, 14,
+        9, 6, 5, 10,
+    ],
+};
+
+/// Trellis code whose state is the previous input symbol.
+struct Trellis {
+    /// Number of data bits per input symbol.
+    bits: usize,
+    /// Constellation point for each state and input symbol, indexed by
+    /// `state << bits | input`.
+    points: &'static [u8],
+}
+
+impl Trellis {
+    /// Number of encoder states.
+    fn states(&self) -> usize { 1 << self.bits }
+
+    /// Get the dibit pair sent for the given state and input symbol.
+    fn dibits(&self, state: usize, input: usize) -> u8 {
+        DIBITS[self.points[state << self.bits | input] as usize]
+    }
+
+    /// Encode the given payload into interleaved bits.
+    fn encode(&self, data: &[u8]) -> [bool; 196] {
+        assert_eq!(data.len() * 8, (POINTS - 1) * self.bits);
+
+        let mut bits = [false; 196];
+        let mut state = 0;
+
+        for p in 0..POINTS {
+            let input = self.input(data, p);
+            let dibits = self.dibits(state, input);
+            let pos = interleave(p) * 4;
+
+            for (i, bit) in bits[pos..pos + 4].iter_mut().enumerate() {
+                *bit = dibits >> (3 - i) & 1 == 1;
+            }
+
+            state = input;
+        }
+
+        bits
+    }
+
+    /// Decode the given hard bits and count the bits that differ from the result.
+    fn decode_hard<const N: usize>(&self, bits: &[bool; 196]) -> ([u8; N], usize) {
+        let soft = bits.iter().map(|&b| if b { -1 } else { 1 }).collect::<Vec<i8>>();
+        let mut data = [0; N];
+        self.decode(&soft[..], &mut data);
+
+        let err = self.encode(&data).iter().zip(bits.iter()).filter(|&(a, b)| a != b).count();
+
+        (data, err)
+    }
+
+    /// Decode the given soft bits and sum the LLR magnitudes that disagree with the
+    /// result.
+    fn decode_soft<S, const N: usize>(&self, bits: &S) -> Option<([u8; N], i32)>
+        where S: SoftBits + ?Sized
+    {
+        if bits.len() != 196 {
+            return None;
+        }
+
+        let mut data = [0; N];
+        self.decode(bits, &mut data);
+
+        let cost = sum_cost(self.encode(&data).iter().enumerate()
+            .filter(|&(i, &b)| bits.hard(i) != b)
+            .map(|(i, _)| bits.llr(i)));
+
+        Some((data, cost))
+    }
+
+    /// Decode the given 196 interleaved soft bits with the Viterbi algorithm into the
+    /// given payload buffer.
+    fn decode<S: SoftBits + ?Sized>(&self, bits: &S, data: &mut [u8]) {
+        let states = self.states();
+
+        let mut metrics = [i64::MIN; 8];
+        let mut next = [i64::MIN; 8];
+        // For each step and destination state, the source state.
+        let mut paths = [[0u8; 8]; POINTS];
+
+        metrics[0] = 0;
+
+        for (p, path) in paths.iter_mut().enumerate() {
+            let pos = interleave(p) * 4;
+            let llrs = [bits.llr(pos), bits.llr(pos + 1), bits.llr(pos + 2), bits.llr(pos + 3)];
+
+            for m in next.iter_mut() {
+                *m = i64::MIN;
+            }
+
+            for (state, &metric) in metrics[..states].iter().enumerate() {
+                if metric == i64::MIN {
+                    continue;
+                }
+
+                for input in 0..states {
+                    let cand = metric + branch_metric(self.dibits(state, input), &llrs);
+
+                    if cand > next[input] {
+                        next[input] = cand;
+                        path[input] = state as u8;
+                    }
+                }
+            }
+
+            metrics = next;
+        }
+
+        // The flushing symbol leaves the encoder in the zero state.
+        let mut state = 0;
+
+        for b in data.iter_mut() {
+            *b = 0;
+        }
+
+        for p in (0..POINTS - 1).rev() {
+            state = paths[p + 1][state] as usize;
+
+            for i in 0..self.bits {
+                let bit = p * self.bits + i;
+                data[bit / 8] |= ((state >> (self.bits - 1 - i) & 1) as u8) << (7 - bit % 8);
+            }
+        }
+    }
+
+    /// Get the input symbol for the given point, with zero for the flushing point.
+    fn input(&self, data: &[u8], p: usize) -> usize {
+        if p == POINTS - 1 {
+            return 0;
+        }
+
+        (0..self.bits).fold(0, |sym, i| {
+            let bit = p * self.bits + i;
+            sym << 1 | (data[bit / 8] >> (7 - bit % 8) & 1) as usize
+        })
+    }
+}
+
+/// Get the transmitted position of the given constellation point, in units of points.
+fn interleave(p: usize) -> usize {
+    const BLOCKS: [usize; 4] = [0, 13, 25, 37];
+    BLOCKS[p % 4] + p / 4
+}
+
+/// Compute the correlation between the given dibit pair and the received soft bits.
+fn branch_metric(dibits: u8, llrs: &[i32; 4]) -> i64 {
+    llrs.iter().enumerate().map(|(i, &l)| {
+        if dibits >> (3 - i) & 1 == 1 { -(l as i64) } else { l as i64 }
+    }).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sim::{RandomSource, SplitMix64};
+    use soft::Scaled;
+
+    fn random_payload<const N: usize>(rng: &mut SplitMix64) -> [u8; N] {
+        let mut data = [0; N];
+
+        for b in data.iter_mut() {
+            *b = rng.next_u64() as u8;
+        }
+
+        data
+    }
+
+    /// Get the constellation points of the given interleaved bits.
+    fn points(bits: &[bool; 196]) -> Vec<u8> {
+        (0..POINTS).map(|p| {
+            let pos = interleave(p) * 4;
+            let dibits = bits[pos..pos + 4].iter().fold(0, |d, &b| d << 1 | b as u8);
+            DIBITS.iter().position(|&d| d == dibits).unwrap() as u8
+        }).collect()
+    }
+
+    #[test]
+    fn test_tables() {
+        let mut seen = [false; 49];
+
+        for p in 0..POINTS {
+            assert!(!seen[interleave(p)]);
+            seen[interleave(p)] = true;
+        }
+
+        let mut seen = [false; 16];
+
+        for &d in &DIBITS {
+            assert!(!seen[d as usize]);
+            seen[d as usize] = true;
+        }
+
+        // Each state's points are one of the two cosets of 8, so every branch out of a
+        // state is at least 2 symbol levels away in one dimension from the others.
+        for state in 0..8 {
+            let row = &RATE_3_4.points[state * 8..(state + 1) * 8];
+            assert!(row.iter().all(|&p| p % 2 == row[0] % 2));
+
+            let mut seen = [false; 16];
+
+            for &p in row {
+                assert!(!seen[p as usize]);
+                seen[p as usize] = true;
+            }
+        }
+
+        // Each state's 4 points of the rate 1/2 code are far apart in both dimensions.
+        for state in 0..4 {
+            let row = &RATE_1_2.points[state * 4..(state + 1) * 4];
+
+            for (i, &a) in row.iter().enumerate() {
+                for &b in &row[i + 1..] {
+                    assert!((DIBITS[a as usize] ^ DIBITS[b as usize]).count_ones() >= 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_dibits() {
+        let mut dibits = [0; 98];
+        dibits[0] = 0b01;
+        dibits[97] = 0b10;
+
+        let bits = from_dibits(&dibits);
+        assert_eq!(&bits[..2], &[false, true]);
+        assert_eq!(&bits[194..], &[true, false]);
+        assert_eq!(bits.iter().filter(|&&b| b).count(), 2);
+
+        let payload = [0x3c; 12];
+        let enc = encode_p25_1_2(&payload);
+        let mut dibits = [0; 98];
+
+        for (d, pair) in dibits.iter_mut().zip(enc.chunks(2)) {
+            *d = (pair[0] as u8) << 1 | pair[1] as u8;
+        }
+
+        assert_eq!(from_dibits(&dibits)[..], enc[..]);
+    }
+
+    #[test]
+    fn test_encode() {
+        // An all-zero payload stays in the zero state.
+        let bits = encode_dmr(&[0; 18]);
+        assert!(points(&bits).iter().all(|&p| p == 0));
+
+        // The first dibit pair sent is the first point.
+        let mut data = [0; 18];
+        data[0] = 0b11100000;
+        let bits = encode_dmr(&data);
+        let pts = points(&bits);
+
+        assert_eq!(&pts[..3], &[14, 6, 0]);
+        assert_eq!(&bits[..4], &[false, true, false, false]);
+
+        let data = [0x5a; 18];
+        assert_eq!(encode_p25_3_4(&data)[..], encode_dmr(&data)[..]);
+
+        // The rate 1/2 code takes dibits.
+        let bits = encode_p25_1_2(&[0; 12]);
+        assert!(points(&bits).iter().all(|&p| p == 0));
+
+        let mut data = [0; 12];
+        data[0] = 0b01100000;
+        let pts = points(&encode_p25_1_2(&data));
+
+        assert_eq!(&pts[..3], &[15, 8, 13]);
+        assert!(pts[3..].iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut rng = SplitMix64::new(23);
+
+        for _ in 0..50 {
+            let data = random_payload(&mut rng);
+            let mut bits = encode_dmr(&data);
+            assert_eq!(decode_dmr(&bits), (data, 0));
+
+            // A single error in every 6th point of the trellis.
+            for p in (0..POINTS).step_by(6) {
+                let i = interleave(p) * 4 + rng.next_u64() as usize % 4;
+                bits[i] = !bits[i];
+            }
+
+            assert_eq!(decode_dmr(&bits), (data, 9));
+            assert_eq!(decode_p25_3_4(&bits), (data, 9));
+        }
+
+        for _ in 0..50 {
+            let data = random_payload(&mut rng);
+            let mut bits = encode_p25_1_2(&data);
+            assert_eq!(decode_p25_1_2(&bits), (data, 0));
+
+            // A single error in every 3rd point of the trellis.
+            for p in (0..POINTS).step_by(3) {
+                let i = interleave(p) * 4 + rng.next_u64() as usize % 4;
+                bits[i] = !bits[i];
+            }
+
+            assert_eq!(decode_p25_1_2(&bits), (data, 17));
+        }
+    }
+
+    #[test]
+    fn test_decode_soft() {
+        let mut rng = SplitMix64::new(29);
+        let data = random_payload(&mut rng);
+
+        let mut soft = encode_dmr(&data).iter()
+            .map(|&b| if b { -100 } else { 100 })
+            .collect::<Vec<i8>>();
+
+        assert_eq!(decode_dmr_soft(&soft[..]), Some((data, 0)));
+
+        // Weak wrong decisions and erasures are outvoted by the strong ones.
+        for p in (0..POINTS).step_by(3) {
+            let i = interleave(p) * 4;
+            soft[i] = -soft[i] / 10;
+            soft[i + 3] = 0;
+        }
+
+        assert_eq!(decode_dmr_soft(&soft[..]), Some((data, 17 * 10)));
+        assert_eq!(decode_dmr_soft(&soft[..195]), None);
+        assert_eq!(decode_p25_3_4_soft(&soft[..]), Some((data, 17 * 10)));
+
+        let data = random_payload(&mut rng);
+
+        let mut soft = encode_p25_1_2(&data).iter()
+            .map(|&b| if b { -1.5 } else { 1.5 })
+            .collect::<Vec<f32>>();
+
+        // Two uncertain bits in every other point.
+        for p in (0..POINTS).step_by(2) {
+            let i = interleave(p) * 4;
+            soft[i] = -soft[i] / 5.0;
+            soft[i + 2] = 0.0;
+        }
+
+        let (decoded, cost) = decode_p25_1_2_soft(&soft[..]).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(cost, 25 * 307);
+
+        // Two certain wrong bits would overflow the cost of huge LLRs, so it saturates.
+        let mut huge = encode_p25_1_2(&data).iter()
+            .map(|&b| if b { -i32::MAX } else { i32::MAX })
+            .collect::<Vec<_>>();
+
+        huge[8] = -huge[8];
+        huge[9] = -huge[9];
+
+        let huge = Scaled::new(&huge, i32::MAX);
+        assert_eq!(decode_p25_1_2_soft(&huge).map(|(_, cost)| cost), Some(i32::MAX));
+        assert_eq!(decode_p25_1_2_soft(&soft[1..]), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Shared test-vector file format.
+//!
+//! Vectors are stored as CSV text with one vector per line, so the same suite can be
+//! consumed by this crate, C ports, and HDL testbenches. Each line holds the code
+//! variant, the received word in hex, and the expected data in hex and corrected error
+//! count, with both of the last two fields left empty for an expected decoding failure:
+//!
+//! ```text
+//! # variant,word,data,errors
+//! cyclic17,0x00000,0x000,0
+//! cyclic17,0x0001f,,
+//! pocsag32,0x7cd215d8,0x0f9a42,0
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::vectors::{self, Variant};
+//!
+//! let suite = vectors::generate(Variant::Cyclic17, (0..64).map(|w| w << 11));
+//!
+//! let mut csv = Vec::new();
+//! vectors::write(&mut csv, &suite).unwrap();
+//!
+//! let parsed = vectors::parse(&String::from_utf8(csv).unwrap()).unwrap();
+//! assert_eq!(parsed, suite);
+//! assert!(parsed.iter().all(|v| v.check()));
+//! ```
+
+use std::io::{self, Write};
+
+/// Code a vector applies to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// The base (17, 9, 5) cyclic code.
+    Cyclic17,
+    /// The POCSAG (32, 21, 6) BCH code.
+    #[cfg(feature = "pocsag")]
+    Pocsag32,
+}
+
+impl Variant {
+    /// Name of the variant in vector files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Cyclic17 => "cyclic17",
+            #[cfg(feature = "pocsag")]
+            Variant::Pocsag32 => "pocsag32",
+        }
+    }
+
+    /// Look up a variant by its name in vector files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cyclic17" => Some(Variant::Cyclic17),
+            #[cfg(feature = "pocsag")]
+            "pocsag32" => Some(Variant::Pocsag32),
+            _ => None,
+        }
+    }
+
+    /// Number of bits in a received word.
+    pub fn word_bits(self) -> u32 {
+        match self {
+            Variant::Cyclic17 => 17,
+            #[cfg(feature = "pocsag")]
+            Variant::Pocsag32 => 32,
+        }
+    }
+
+    /// Decode the given word with this crate's decoder for the variant.
+    pub fn decode(self, word: u32) -> Option<(u32, usize)> {
+        match self {
+            Variant::Cyclic17 => ::decode(word).map(|(data, err)| (data as u32, err)),
+            #[cfg(feature = "pocsag")]
+            Variant::Pocsag32 => ::pocsag::decode(word),
+        }
+    }
+
+    /// Number of hex digits used to write a word and its data.
+    fn digits(self) -> (usize, usize) {
+        match self {
+            Variant::Cyclic17 => (5, 3),
+            #[cfg(feature = "pocsag")]
+            Variant::Pocsag32 => (8, 6),
+        }
+    }
+}
+
+/// A received word and its expected decoding.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Vector {
+    /// Code the vector applies to.
+    pub variant: Variant,
+    /// Received word.
+    pub word: u32,
+    /// Expected decoder result, as `Some((data, err))` or `None`.
+    pub expected: Option<(u32, usize)>,
+}
+
+impl Vector {
+    /// Check if this crate's decoder gives the expected result.
+    pub fn check(&self) -> bool {
+        self.variant.decode(self.word) == self.expected
+    }
+}
+
+/// Error parsing a vector file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number of the invalid line.
+    pub line: usize,
+    /// Description of the problem.
+    pub msg: &'static str,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Build vectors for the given words with the expected results taken from this crate's
+/// decoder.
+pub fn generate<I>(variant: Variant, words: I) -> Vec<Vector> where
+    I: IntoIterator<Item = u32>
+{
+    words.into_iter()
+        .map(|word| Vector { variant, word, expected: variant.decode(word) })
+        .collect()
+}
+
+/// Parse the vectors in the given file contents.
+pub fn parse(text: &str) -> Result<Vec<Vector>, ParseError> {
+    text.lines().enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|&(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| parse_line(line).map_err(|msg| ParseError { line: n, msg }))
+        .collect()
+}
+
+/// Write the given vectors in the file format, with a leading header comment.
+pub fn write<W: Write>(mut w: W, vectors: &[Vector]) -> io::Result<()> {
+    writeln!(w, "# variant,word,data,errors")?;
+
+    for v in vectors {
+        let (wd, dd) = v.variant.digits();
+        write!(w, "{},0x{:02$x},", v.variant.name(), v.word, wd)?;
+
+        match v.expected {
+            Some((data, err)) => writeln!(w, "0x{:01$x},{2}", data, dd, err)?,
+            None => writeln!(w, ",")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a single non-comment line.
+fn parse_line(line: &str) -> Result<Vector, &'static str> {
+    let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+
+    if fields.len() != 4 {
+        return Err("expected 4 fields");
+    }
+
+    let variant = Variant::from_name(fields[0]).ok_or("unknown variant")?;
+    let word = parse_hex(fields[1]).ok_or("invalid word")?;
+
+    if variant.word_bits() < 32 && word >> variant.word_bits() != 0 {
+        return Err("word too long for variant");
+    }
+
+    let expected = match (fields[2], fields[3]) {
+        ("", "") => None,
+        (data, err) => Some((
+            parse_hex(data).ok_or("invalid data")?,
+            err.parse().map_err(|_| "invalid error count")?,
+        )),
+    };
+
+    Ok(Vector { variant, word, expected })
+}
+
+/// Parse a `0x`-prefixed hex number.
+fn parse_hex(s: &str) -> Option<u32> {
+    if !s.starts_with("0x") {
+        return None;
+    }
+
+    u32::from_str_radix(&s[2..], 16).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "pocsag")]
+    fn test_parse() {
+        let text = "# variant,word,data,errors\n\
+                    \n\
+                    cyclic17,0x00000,0x000,0\n\
+                    cyclic17, 0x0001f , , \n\
+                    pocsag32,0x7cd215d8,0x0f9a42,0\n";
+
+        let vectors = parse(text).unwrap();
+
+        assert_eq!(vectors, vec![
+            Vector { variant: Variant::Cyclic17, word: 0, expected: Some((0, 0)) },
+            Vector { variant: Variant::Cyclic17, word: 0x1f, expected: None },
+            Vector { variant: Variant::Pocsag32, word: 0x7cd215d8, expected: Some((0x0f9a42, 0)) },
+        ]);
+
+        assert!(vectors.iter().all(Vector::check));
+
+        assert_eq!(parse("cyclic17,0x0,0x0\n"),
+                   Err(ParseError { line: 1, msg: "expected 4 fields" }));
+        assert_eq!(parse("#\ngolay,0x0,0x0,0"),
+                   Err(ParseError { line: 2, msg: "unknown variant" }));
+        assert_eq!(parse("cyclic17,0x20000,,"),
+                   Err(ParseError { line: 1, msg: "word too long for variant" }));
+        assert_eq!(parse("cyclic17,12,,"), Err(ParseError { line: 1, msg: "invalid word" }));
+        assert_eq!(parse("cyclic17,0x0,0x0,"),
+                   Err(ParseError { line: 1, msg: "invalid error count" }));
+    }
+
+    #[test]
+    #[cfg(feature = "pocsag")]
+    fn test_write() {
+        let mut out = Vec::new();
+
+        write(&mut out, &[
+            Vector { variant: Variant::Cyclic17, word: 0x1f, expected: None },
+            Vector { variant: Variant::Pocsag32, word: 0x7a89c197, expected: Some((0x0f5138, 0)) },
+        ]).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "# variant,word,data,errors\n\
+                    cyclic17,0x0001f,,\n\
+                    pocsag32,0x7a89c197,0x0f5138,0\n");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        #[cfg_attr(not(feature = "pocsag"), allow(unused_mut))]
+        let mut suite = generate(Variant::Cyclic17, (0..1 << 17).step_by(97));
+
+        #[cfg(feature = "pocsag")]
+        suite.extend(generate(Variant::Pocsag32, (0..1000).map(|i| i * 4294967)));
+
+        let mut out = Vec::new();
+        write(&mut out, &suite).unwrap();
+
+        assert_eq!(parse(&String::from_utf8(out).unwrap()).unwrap(), suite);
+        assert!(suite.iter().all(Vector::check));
+        assert!(suite.iter().any(|v| v.expected.is_none()));
+    }
+}
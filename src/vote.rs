@@ -0,0 +1,166 @@
+//! Combining the same word received by multiple receivers.
+//!
+//! In simulcast and voting receiver setups, each site delivers its own copy of every
+//! word. These routines either select the copy that decoded with the fewest corrections,
+//! or combine the copies bit by bit before decoding, which can recover a word no single
+//! receiver could.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use cai_cyclic::vote;
+//!
+//! let w = cai_cyclic::encode(0b111000111);
+//!
+//! // Every receiver has 3 errors, but in different places.
+//! let rx = [w ^ 0b111, w ^ 0b111 << 7, w ^ 0b111 << 14];
+//!
+//! assert!(rx.iter().all(|&w| cai_cyclic::decode(w).map(|(d, _)| d) != Some(0b111000111)));
+//! assert_eq!(vote::majority(&rx), Some((0b111000111, 0)));
+//! ```
+
+use soft::{self, Scaled, SoftBits};
+
+/// Decode each received copy and select the one with the fewest corrected errors.
+///
+/// If any copy decoded, return `Some((idx, data, err))`, where `idx` is the index of the
+/// selected copy, `data` is its 9 data bits, and `err` is its number of corrected bits.
+/// Ties go to the earliest copy. Otherwise, return `None`.
+pub fn select(words: &[u32]) -> Option<(usize, u16, usize)> {
+    words.iter().enumerate()
+        .filter_map(|(idx, &w)| ::decode(w).map(|(data, err)| (idx, data, err)))
+        .min_by_key(|&(_, _, err)| err)
+}
+
+/// Combine the received copies by a per-bit majority vote, then decode the result.
+///
+/// Ties are broken toward the earliest copy's bit. The result is the same as
+/// [`decode`](../fn.decode.html) on the combined word.
+pub fn majority(words: &[u32]) -> Option<(u16, usize)> {
+    let first = *words.first()?;
+
+    let word = (0..17).fold(0, |word, bit| {
+        let ones = words.iter().filter(|&&w| w >> bit & 1 == 1).count();
+        let zeros = words.len() - ones;
+
+        let one = if ones == zeros { first >> bit & 1 == 1 } else { ones > zeros };
+
+        word | (one as u32) << bit
+    });
+
+    ::decode(word)
+}
+
+/// Combine the soft bits of the received copies by summing their LLRs, then
+/// soft-decision decode the result.
+///
+/// Summing LLRs is optimal when the receivers' noise is independent. The returned cost
+/// is in units of the summed LLRs, as with [`soft::decode`](../soft/fn.decode.html),
+/// except that sums too large for the cost of a whole word are first scaled down by a
+/// common factor. Return `None` if there are no copies or any copy doesn't have 17 soft
+/// bits.
+pub fn combine_soft<S: SoftBits>(copies: &[S]) -> Option<(u16, i32)> {
+    if copies.is_empty() || copies.iter().any(|s| s.len() != 17) {
+        return None;
+    }
+
+    let mut sums = [0i64; 17];
+
+    for (i, sum) in sums.iter_mut().enumerate() {
+        *sum = copies.iter().map(|s| s.llr(i) as i64).sum();
+    }
+
+    let scale = copies.iter().map(|s| s.full_scale() as i64).sum::<i64>();
+
+    // Keep the summed magnitudes of all 17 bits within range.
+    let limit = i32::MAX as i64 / 17;
+    let div = (scale + limit - 1) / limit;
+
+    let mut llrs = [0; 17];
+
+    for (llr, &sum) in llrs.iter_mut().zip(&sums) {
+        *llr = (sum / div) as i32;
+    }
+
+    soft::decode(&Scaled::new(&llrs, (scale / div) as i32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use encode;
+
+    #[test]
+    fn test_select() {
+        let w = encode(0b010101010);
+
+        assert_eq!(select(&[]), None);
+        assert_eq!(select(&[w ^ 0b11, w ^ 1, w ^ 1 << 5]), Some((1, 0b010101010, 1)));
+        assert_eq!(select(&[w ^ 0b11, w]), Some((1, 0b010101010, 0)));
+        assert_eq!(select(&[w ^ 0b11, w ^ 1 << 3]), Some((1, 0b010101010, 1)));
+    }
+
+    #[test]
+    fn test_majority() {
+        let w = encode(0b010101010);
+
+        assert_eq!(majority(&[]), None);
+        assert_eq!(majority(&[w ^ 1]), Some((0b010101010, 1)));
+        assert_eq!(majority(&[w ^ 0b1111, w, w ^ 0b1111 << 8]), Some((0b010101010, 0)));
+
+        // Ties go to the first copy.
+        assert_eq!(majority(&[w, w ^ 0b11111]), Some((0b010101010, 0)));
+    }
+
+    #[test]
+    fn test_combine_soft() {
+        let w = encode(0b000111000);
+
+        let llrs = |errs: u32, conf: i8| {
+            let mut llrs = [0i8; 17];
+
+            for (i, llr) in llrs.iter_mut().enumerate() {
+                let bit = (w ^ errs) >> (16 - i) & 1 == 1;
+                *llr = if bit { -conf } else { conf };
+            }
+
+            llrs
+        };
+
+        // A strong copy outvotes two weak copies with the same errors.
+        let copies = [llrs(0b111, 20), llrs(0b111, 20), llrs(0, 100)];
+        assert_eq!(combine_soft(&copies), Some((0b000111000, 0)));
+
+        let copies = [llrs(0b1111, 50), llrs(0b1111 << 6, 50), llrs(0b1111 << 12, 50)];
+        assert_eq!(combine_soft(&copies).map(|(d, _)| d), Some(0b000111000));
+
+        // Summing copies at the largest scale doesn't overflow.
+        let big = |errs: u32| {
+            let mut llrs = [0i32; 17];
+
+            for (i, llr) in llrs.iter_mut().enumerate() {
+                let bit = (w ^ errs) >> (16 - i) & 1 == 1;
+                *llr = if bit { -i32::MAX } else { i32::MAX };
+            }
+
+            llrs
+        };
+
+        let (a, b, c) = (big(0b11), big(0b11 << 5), big(0));
+        let copies = [Scaled::new(&a, i32::MAX), Scaled::new(&b, i32::MAX),
+                      Scaled::new(&c, i32::MAX)];
+        let (data, cost) = combine_soft(&copies).unwrap();
+        assert_eq!(data, 0b000111000);
+        assert_eq!(cost, 0);
+
+        let (a, b, c) = (big(1), big(1), big(0));
+        let copies = [Scaled::new(&a, i32::MAX), Scaled::new(&b, i32::MAX),
+                      Scaled::new(&c, i32::MAX)];
+        let (data, cost) = combine_soft(&copies).unwrap();
+        assert_eq!(data, 0b000111000);
+        assert!(cost > 0);
+
+        assert_eq!(combine_soft::<[i8; 17]>(&[]), None);
+        assert_eq!(combine_soft(&[&[0i8; 16][..]]), None);
+    }
+}